@@ -1,27 +1,47 @@
 use arboard::Clipboard;
 use ratatui::{widgets::ListState, DefaultTerminal};
+use std::{
+    cmp::Reverse,
+    env,
+    sync::mpsc::Receiver,
+    time::{SystemTime, UNIX_EPOCH},
+};
 
 use crate::{
     bookmarks::*,
     config::{self, Config},
-    launcher::LaunchableApp,
-    ui::{Control, InputHandler},
+    launcher::{self, InstalledBrowser, LaunchableApp, Opener, WindowMode},
+    ui::{Action, Control, InputHandler, KeyBinding, QrCodeView, APP_ACTIONS, BOOKMARK_ACTIONS},
 };
 
 pub struct App {
     should_exit: bool,
     pub bookmark_list: BookmarkList,
+    pub history_list: HistoryList,
     pub app_list: AppList,
     pub input_str: String,
     pub state: AppState,
     pub title: String,
     pub status_message: StatusMessage,
     pub config_manager: ConfigManager,
+    pub editor: Option<BookmarkEditor>,
+    pub open_with: Option<OpenWithMenu>,
+    pub action_menu: Option<ActionMenu>,
+    /// Bookmark whose details are shown.
+    pub details: Option<Bookmark>,
+    /// QR code of the URL of a bookmark, for opening it on a phone.
+    pub qr_code: Option<QrCodeView>,
+    /// Opens URLs by the routes in the config, or in the browser a bookmark came from.
+    opener: Opener,
+    store: Option<Store>,
+    /// Receives fresh imports whenever the watcher of the bookmark index finds a changed source.
+    updates: Receiver<Import>,
     input_handler: InputHandler,
 }
 
 pub enum AppState {
     Bookmarks,
+    History,
     Launcher,
 }
 
@@ -32,13 +52,37 @@ pub enum StatusMessage {
 }
 
 impl App {
-    pub fn new(bookmarks: Vec<Bookmark>, apps: Vec<LaunchableApp>, config: Config) -> App {
+    pub fn new(
+        import: Import,
+        updates: Receiver<Import>,
+        store: Option<Store>,
+        apps: Vec<LaunchableApp>,
+        config: Config,
+    ) -> App {
+        let action_key = config
+            .action_key
+            .as_deref()
+            .map(str::parse::<KeyBinding>)
+            .transpose();
         let mut app = App {
             should_exit: false,
             bookmark_list: BookmarkList {
-                bookmarks,
+                bookmarks: Vec::new(),
+                imported: import.bookmarks,
+                search_engines: import.search_engines,
+                import_errors: import.errors,
+                skipped: import.skipped,
+                state: ListState::default(),
+            },
+            history_list: HistoryList {
+                entries: import.history,
                 state: ListState::default(),
             },
+            opener: Opener::new(
+                launcher::locate_browsers(&apps),
+                config.routes.clone(),
+                env::var("BROWSER").ok(),
+            ),
             app_list: AppList {
                 apps,
                 state: ListState::default(),
@@ -47,17 +91,42 @@ impl App {
             state: AppState::Bookmarks,
             title: String::new(),
             status_message: StatusMessage::None,
-            input_handler: InputHandler::new(),
+            input_handler: InputHandler::new(
+                action_key
+                    .as_ref()
+                    .ok()
+                    .copied()
+                    .flatten()
+                    .unwrap_or_default(),
+            ),
             config_manager: ConfigManager::new(config),
+            editor: None,
+            open_with: None,
+            action_menu: None,
+            details: None,
+            qr_code: None,
+            store,
+            updates,
         };
 
+        if let Err(e) = app.refresh_bookmarks() {
+            app.bookmark_list
+                .import_errors
+                .push(format!("Failed to read bookmark store: {}", e));
+        }
         app.set_state(AppState::Bookmarks);
+        if let Err(e) = action_key {
+            app.status_message = StatusMessage::Error(format!("Invalid action key: {}", e));
+        }
         app
     }
 
     pub fn run(&mut self, mut terminal: DefaultTerminal) -> anyhow::Result<()> {
         while !self.should_exit {
             terminal.draw(|frame| frame.render_widget(&mut *self, frame.area()))?;
+            if let Ok(import) = self.updates.try_recv() {
+                self.apply_import(import);
+            }
             let control: Control = self.input_handler.read();
 
             match control {
@@ -68,33 +137,119 @@ impl App {
                     self.input_str.pop();
                 }
                 Control::SetBookmarksState => self.set_state(AppState::Bookmarks),
+                Control::SetHistoryState => self.set_state(AppState::History),
                 Control::SetLauncherState => self.set_state(AppState::Launcher),
                 Control::SelectNextBookmark => self.bookmark_list.state.select_next(),
                 Control::SelectPreviousBookmark => self.bookmark_list.state.select_previous(),
-                Control::OpenBookmark => self.open_bookmark()?,
+                Control::SelectNextHistoryEntry => self.history_list.state.select_next(),
+                Control::SelectPreviousHistoryEntry => self.history_list.state.select_previous(),
+                Control::OpenHistoryEntry => self.open_history_entry(),
                 Control::Clear => self.clear_input(),
                 Control::ConfigVisible(visible) => self.set_config_visibile(visible),
                 Control::None => {}
                 Control::SelectNextApp => self.app_list.state.select_next(),
                 Control::SelectPreviousApp => self.app_list.state.select_previous(),
-                Control::LaunchApp => self.launch_app()?,
+                Control::Run(action) => self.run_action(action),
                 Control::ConfigNext => self.config_manager.next(),
                 Control::ConfigPrevious => self.config_manager.previous(),
+                Control::EditorInput(val) => {
+                    if let Some(editor) = &mut self.editor {
+                        editor.active_value().push_str(val.as_str());
+                    }
+                }
+                Control::EditorDelete => {
+                    if let Some(editor) = &mut self.editor {
+                        editor.active_value().pop();
+                    }
+                }
+                Control::EditorPaste => self.paste_to_editor(),
+                Control::EditorNextField => {
+                    if let Some(editor) = &mut self.editor {
+                        editor.next_field();
+                    }
+                }
+                Control::EditorPreviousField => {
+                    if let Some(editor) = &mut self.editor {
+                        editor.previous_field();
+                    }
+                }
+                Control::SaveBookmark => self.save_bookmark(),
+                Control::CloseEditor => self.set_editor(None),
+                Control::ShowActions => self.show_actions(),
+                Control::ActionsNext => {
+                    if let Some(menu) = &mut self.action_menu {
+                        menu.state.select_next();
+                    }
+                }
+                Control::ActionsPrevious => {
+                    if let Some(menu) = &mut self.action_menu {
+                        menu.state.select_previous();
+                    }
+                }
+                Control::RunSelectedAction => {
+                    if let Some(action) = self.action_menu.as_ref().and_then(ActionMenu::selected) {
+                        self.set_action_menu(None);
+                        self.run_action(action);
+                    }
+                }
+                Control::CloseActions => self.set_action_menu(None),
+                Control::CloseDetails => self.set_details(None),
+                Control::CloseQrCode => self.set_qr_code(None),
+                Control::OpenWithNext => {
+                    if let Some(menu) = &mut self.open_with {
+                        menu.state.select_next();
+                    }
+                }
+                Control::OpenWithPrevious => {
+                    if let Some(menu) = &mut self.open_with {
+                        menu.state.select_previous();
+                    }
+                }
+                Control::OpenWithSelected(mode) => self.open_with_selected(mode),
+                Control::CloseOpenWith => self.set_open_with(None),
             }
         }
         Ok(())
     }
 
     pub fn search_bookmarks(&self) -> Vec<Bookmark> {
-        self.bookmark_list
+        let now = now();
+        let mut matches: Vec<Bookmark> = self
+            .bookmark_list
             .bookmarks
             .iter()
-            .filter(|b| {
-                !&self.input_str.is_empty()
-                    && b.name
-                        .to_uppercase()
-                        .contains(&self.input_str.to_uppercase())
-            })
+            .filter(|b| !&self.input_str.is_empty() && b.matches(&self.input_str))
+            .cloned()
+            .collect();
+        matches.sort_by_key(|b| Reverse(b.rank(now)));
+
+        // Searches are offered as synthetic bookmarks: a keyword search before the matches, and
+        // a web search when nothing matches.
+        let config = &self.config_manager.config;
+        let engines: Vec<SearchEngine> = config
+            .search_engines
+            .iter()
+            .chain(&self.bookmark_list.search_engines)
+            .cloned()
+            .collect();
+        if let Some(search) = keyword_search(&engines, &self.input_str) {
+            matches.insert(0, search);
+        }
+        let web_search_template = config.web_search.as_deref().unwrap_or(DEFAULT_WEB_SEARCH);
+        if matches.is_empty()
+            && !self.input_str.trim().is_empty()
+            && !web_search_template.is_empty()
+        {
+            matches.push(web_search(web_search_template, &self.input_str));
+        }
+        matches
+    }
+
+    pub fn search_history(&self) -> Vec<HistoryEntry> {
+        self.history_list
+            .entries
+            .iter()
+            .filter(|e| !&self.input_str.is_empty() && e.matches(&self.input_str))
             .cloned()
             .collect()
     }
@@ -127,37 +282,335 @@ impl App {
         }
     }
 
-    fn open_bookmark(&self) -> anyhow::Result<()> {
-        if let Some(i) = self.bookmark_list.state.selected() {
-            let items = self.search_bookmarks();
-            if i < items.len() {
-                let item = &items[i];
-                open::that(&item.url)?;
+    /// Runs an action on the selected bookmark or app. Every key and menu entry that acts on an
+    /// item goes through here.
+    fn run_action(&mut self, action: Action) {
+        match action {
+            Action::Open => self.open_bookmark(),
+            Action::OpenWith => self.show_open_with(),
+            Action::CopyUrl => {
+                if let Some(bookmark) = self.selected_bookmark() {
+                    self.copy_to_clipboard(&bookmark.url, "URL");
+                }
+            }
+            Action::CopyLink(format) => {
+                if let Some(bookmark) = self.selected_bookmark() {
+                    self.copy_to_clipboard(&format_link(&bookmark, format), "link");
+                }
+            }
+            Action::ShowDetails => self.set_details(self.selected_bookmark()),
+            Action::ShowQrCode => {
+                if let Some(bookmark) = self.selected_bookmark() {
+                    match QrCodeView::new(&bookmark.url) {
+                        Ok(view) => self.set_qr_code(Some(view)),
+                        Err(e) => self.status_message = StatusMessage::Error(e.to_string()),
+                    }
+                }
             }
+            Action::Add => self.add_bookmark(),
+            Action::Edit => self.edit_bookmark(),
+            Action::Delete => self.delete_bookmark(),
+            Action::Launch => {
+                if let Some(app) = self.selected_app() {
+                    self.report_failure(app.launch(), "Failed to launch app");
+                }
+            }
+            Action::LaunchInTerminal => {
+                if let Some(app) = self.selected_app() {
+                    self.report_failure(app.launch_in_terminal(), "Failed to launch app");
+                }
+            }
+            Action::CopyExec => {
+                if let Some(app) = self.selected_app() {
+                    self.copy_to_clipboard(&app.exec_handle, "Exec line");
+                }
+            }
+            Action::RevealFile => {
+                if let Some(app) = self.selected_app() {
+                    self.report_failure(app.reveal(), "Failed to reveal desktop file");
+                }
+            }
+        }
+    }
+
+    /// Shows the actions for the selected bookmark or app.
+    fn show_actions(&mut self) {
+        let menu = match self.state {
+            AppState::Bookmarks => self.selected_bookmark().map(|b| {
+                let actions: Vec<Action> = BOOKMARK_ACTIONS
+                    .into_iter()
+                    .filter(|a| a.is_available_for(&b))
+                    .collect();
+                ActionMenu::new(&b.name, &actions)
+            }),
+            AppState::Launcher => self
+                .selected_app()
+                .map(|a| ActionMenu::new(&a.name, &APP_ACTIONS)),
+            AppState::History => None,
+        };
+        if menu.is_some() {
+            self.set_action_menu(menu);
         }
+    }
+
+    fn set_action_menu(&mut self, menu: Option<ActionMenu>) {
+        self.input_handler.set_actions_visible(menu.is_some());
+        self.action_menu = menu;
+    }
+
+    fn set_details(&mut self, bookmark: Option<Bookmark>) {
+        self.input_handler.set_details_visible(bookmark.is_some());
+        self.details = bookmark;
+    }
+
+    fn set_qr_code(&mut self, view: Option<QrCodeView>) {
+        self.input_handler.set_qr_code_visible(view.is_some());
+        self.qr_code = view;
+    }
+
+    fn copy_to_clipboard(&mut self, text: &str, what: &str) {
+        let result = Clipboard::new().and_then(|mut clipboard| clipboard.set_text(text));
+        self.status_message = match result {
+            Ok(()) => StatusMessage::Success(format!("Copied {what}")),
+            Err(why) => StatusMessage::Error(format!("Failed to copy {what}: {why}")),
+        };
+    }
+
+    /// Shows the error of a failed action in the status bar.
+    fn report_failure(&mut self, result: anyhow::Result<()>, message: &str) {
+        if let Err(e) = result {
+            self.status_message = StatusMessage::Error(format!("{message}: {e}"));
+        }
+    }
+
+    /// Opens the selected bookmark by the first matching route, or in the browser and profile
+    /// it was imported from, see [`Opener`].
+    fn open_bookmark(&mut self) {
+        let Some(item) = self.selected_bookmark() else {
+            return;
+        };
+        if let Err(e) = self.opener.open(&item.url, Some(&item)) {
+            self.status_message = StatusMessage::Error(format!("Failed to open bookmark: {}", e));
+        }
+    }
+
+    fn show_open_with(&mut self) {
+        let Some(bookmark) = self.selected_bookmark() else {
+            return;
+        };
+        if self.opener.browsers.is_empty() {
+            self.status_message = StatusMessage::Error("No installed browsers found".to_string());
+            return;
+        }
+        let menu = OpenWithMenu::new(&self.opener.browsers, &bookmark);
+        self.set_open_with(Some(menu));
+    }
+
+    fn open_with_selected(&mut self, mode: WindowMode) {
+        let Some(menu) = &self.open_with else {
+            return;
+        };
+        let Some((browser, profile)) = menu.selected() else {
+            return;
+        };
+        let label = OpenWithMenu::label(browser, profile);
+        match browser.open(&menu.url, profile, mode, &[]) {
+            Ok(()) => {
+                self.status_message = StatusMessage::Success(format!("Opened in {label}"));
+                self.set_open_with(None);
+            }
+            Err(e) => {
+                self.status_message =
+                    StatusMessage::Error(format!("Failed to open in {label}: {e}"))
+            }
+        }
+    }
+
+    fn set_open_with(&mut self, menu: Option<OpenWithMenu>) {
+        self.input_handler.set_open_with_visible(menu.is_some());
+        self.open_with = menu;
+    }
+
+    fn selected_bookmark(&self) -> Option<Bookmark> {
+        let i = self.bookmark_list.state.selected()?;
+        self.search_bookmarks().into_iter().nth(i)
+    }
+
+    /// Rebuilds the bookmark list from the store and the imported bookmarks. Stored bookmarks
+    /// come first, so their name and folder win over imported bookmarks with the same URL.
+    fn refresh_bookmarks(&mut self) -> anyhow::Result<()> {
+        let mut bookmarks = match &self.store {
+            Some(store) => store.list()?,
+            None => Vec::new(),
+        };
+        bookmarks.extend(self.bookmark_list.imported.iter().cloned());
+        self.bookmark_list.bookmarks = merge(bookmarks);
         Ok(())
     }
 
-    fn launch_app(&self) -> anyhow::Result<()> {
-        if let Some(i) = self.app_list.state.selected() {
-            let items = self.search_apps();
+    /// Replaces the imported bookmarks and history with a fresh import, keeping the query and
+    /// the selected bookmark and history entry.
+    fn apply_import(&mut self, import: Import) {
+        let selected_url = self.selected_bookmark().map(|b| b.url);
+        let selected_history_url = self
+            .history_list
+            .state
+            .selected()
+            .and_then(|i| self.search_history().into_iter().nth(i))
+            .map(|e| e.url);
+
+        self.bookmark_list.imported = import.bookmarks;
+        self.bookmark_list.search_engines = import.search_engines;
+        self.bookmark_list.import_errors = import.errors;
+        self.bookmark_list.skipped = import.skipped;
+        self.history_list.entries = import.history;
+        if let Err(e) = self.refresh_bookmarks() {
+            self.bookmark_list
+                .import_errors
+                .push(format!("Failed to read bookmark store: {}", e));
+        }
+
+        if let Some(url) = selected_url {
+            let position = self.search_bookmarks().iter().position(|b| b.url == url);
+            self.bookmark_list.state.select(position);
+        }
+        if let Some(url) = selected_history_url {
+            let position = self.search_history().iter().position(|e| e.url == url);
+            self.history_list.state.select(position);
+        }
+        if let AppState::Bookmarks = self.state {
+            self.status_message = self.bookmarks_status();
+        }
+    }
+
+    fn add_bookmark(&mut self) {
+        // Prefill the URL from the clipboard, as bookmarks are usually copied from a browser.
+        let url = Clipboard::new()
+            .and_then(|mut clipboard| clipboard.get_text())
+            .map(|text| text.lines().next().unwrap_or_default().trim().to_string())
+            .unwrap_or_default();
+        self.set_editor(Some(BookmarkEditor::new(["", url.as_str(), ""], None)));
+    }
+
+    fn edit_bookmark(&mut self) {
+        let Some(bookmark) = self.selected_bookmark() else {
+            return;
+        };
+        if !bookmark.is_stored() {
+            self.status_message =
+                StatusMessage::Error("Only bookmarks added in hhand can be edited".to_string());
+            return;
+        }
+        self.set_editor(Some(BookmarkEditor::new(
+            [&bookmark.name, &bookmark.url, &bookmark.folder],
+            Some(bookmark.url.clone()),
+        )));
+    }
+
+    fn delete_bookmark(&mut self) {
+        let Some(bookmark) = self.selected_bookmark() else {
+            return;
+        };
+        if !bookmark.is_stored() {
+            self.status_message =
+                StatusMessage::Error("Only bookmarks added in hhand can be deleted".to_string());
+            return;
+        }
+        let result = match &self.store {
+            Some(store) => store.delete(&bookmark.url),
+            None => Err(anyhow::anyhow!("Bookmark store is unavailable")),
+        };
+        self.status_message = match result.and_then(|_| self.refresh_bookmarks()) {
+            Ok(()) => StatusMessage::Success(format!("Deleted {}", bookmark.name)),
+            Err(e) => StatusMessage::Error(format!("Failed to delete bookmark: {}", e)),
+        };
+    }
+
+    fn save_bookmark(&mut self) {
+        let Some(editor) = &self.editor else {
+            return;
+        };
+        let [name, url, folder] = editor.fields.clone().map(|f| f.trim().to_string());
+        if url.is_empty() {
+            self.status_message = StatusMessage::Error("A bookmark needs a URL".to_string());
+            return;
+        }
+
+        let mut bookmark = Bookmark::new(if name.is_empty() { &url } else { &name }, &url);
+        bookmark.folder = folder;
+
+        // Editing keeps the date the bookmark was added, which the store does not update.
+        let result = match (&self.store, &editor.original_url) {
+            (None, _) => Err(anyhow::anyhow!("Bookmark store is unavailable")),
+            (Some(store), None) => {
+                bookmark.date_added = Some(now());
+                store.add(&bookmark)
+            }
+            (Some(store), Some(original_url)) => store.update(original_url, &bookmark),
+        };
+        match result.and_then(|_| self.refresh_bookmarks()) {
+            Ok(()) => {
+                self.status_message = StatusMessage::Success(format!("Saved {}", bookmark.name));
+                self.set_editor(None);
+            }
+            Err(e) => {
+                self.status_message =
+                    StatusMessage::Error(format!("Failed to save bookmark: {}", e))
+            }
+        }
+    }
+
+    fn paste_to_editor(&mut self) {
+        match Clipboard::new() {
+            Err(why) => {
+                self.status_message =
+                    StatusMessage::Error(format!("Failed to initialize clipboard: {why}"))
+            }
+            Ok(mut clipboard) => {
+                if let (Ok(text), Some(editor)) = (clipboard.get_text(), &mut self.editor) {
+                    editor.active_value().push_str(text.trim());
+                }
+            }
+        }
+    }
+
+    fn set_editor(&mut self, editor: Option<BookmarkEditor>) {
+        self.input_handler.set_editor_visible(editor.is_some());
+        self.editor = editor;
+    }
+
+    fn open_history_entry(&mut self) {
+        if let Some(i) = self.history_list.state.selected() {
+            let items = self.search_history();
             if i < items.len() {
                 let item = &items[i];
-                item.launch()?;
+                if let Err(e) = self.opener.open(&item.url, None) {
+                    self.status_message =
+                        StatusMessage::Error(format!("Failed to open page: {}", e));
+                }
             }
         }
-        Ok(())
+    }
+
+    fn selected_app(&self) -> Option<LaunchableApp> {
+        let i = self.app_list.state.selected()?;
+        self.search_apps().into_iter().nth(i)
     }
 
     fn set_state(&mut self, new_state: AppState) {
         match new_state {
             AppState::Bookmarks => {
                 self.title = "Search for bookmark".to_string();
+                self.status_message = self.bookmarks_status();
+                self.input_handler.set_mode(AppState::Bookmarks);
+            }
+            AppState::History => {
+                self.title = "Search history".to_string();
+                self.input_handler.set_mode(AppState::History);
                 self.status_message = StatusMessage::Success(format!(
-                    "Loaded {} bookmarks",
-                    self.bookmark_list.bookmarks.len()
+                    "Loaded {} history entries",
+                    self.history_list.entries.len()
                 ));
-                self.input_handler.set_mode(AppState::Bookmarks);
             }
             AppState::Launcher => {
                 self.title = "Launch app".to_string();
@@ -171,6 +624,17 @@ impl App {
         self.input_str.clear();
     }
 
+    fn bookmarks_status(&self) -> StatusMessage {
+        let mut loaded = format!("Loaded {} bookmarks", self.bookmark_list.bookmarks.len());
+        if self.bookmark_list.skipped > 0 {
+            loaded.push_str(&format!(" ({} skipped)", self.bookmark_list.skipped));
+        }
+        match self.bookmark_list.import_errors.first() {
+            Some(e) => StatusMessage::Error(format!("{}. {}", loaded, e)),
+            None => StatusMessage::Success(loaded),
+        }
+    }
+
     fn clear_input(&mut self) {
         self.input_str.clear();
     }
@@ -182,7 +646,143 @@ impl App {
 }
 
 pub struct BookmarkList {
+    /// Stored and imported bookmarks, merged into one list.
     bookmarks: Vec<Bookmark>,
+    imported: Vec<Bookmark>,
+    /// Search engines imported from browsers, tried after the ones in the config.
+    search_engines: Vec<SearchEngine>,
+    import_errors: Vec<String>,
+    skipped: usize,
+    pub state: ListState,
+}
+
+/// Labels of the fields of the [`BookmarkEditor`], in order.
+pub const EDITOR_FIELDS: [&str; 3] = ["Name", "URL", "Folder"];
+
+/// Form for adding a bookmark to the store, or editing a stored one.
+pub struct BookmarkEditor {
+    /// Values of the name, URL and folder fields.
+    pub fields: [String; 3],
+    /// Index of the field receiving input.
+    pub active_field: usize,
+    /// URL of the stored bookmark being edited, or `None` when adding a bookmark.
+    original_url: Option<String>,
+}
+
+impl BookmarkEditor {
+    fn new(fields: [&str; 3], original_url: Option<String>) -> Self {
+        BookmarkEditor {
+            fields: fields.map(str::to_string),
+            // Start in the first empty field, as the URL is often prefilled.
+            active_field: fields.iter().position(|f| f.is_empty()).unwrap_or(0),
+            original_url,
+        }
+    }
+
+    pub fn is_adding(&self) -> bool {
+        self.original_url.is_none()
+    }
+
+    fn active_value(&mut self) -> &mut String {
+        &mut self.fields[self.active_field]
+    }
+
+    fn next_field(&mut self) {
+        self.active_field = (self.active_field + 1) % self.fields.len();
+    }
+
+    fn previous_field(&mut self) {
+        self.active_field = (self.active_field + self.fields.len() - 1) % self.fields.len();
+    }
+}
+
+/// Menu for opening a bookmark with a chosen browser, profile and kind of window.
+pub struct OpenWithMenu {
+    url: String,
+    /// Installed browsers with one of their profiles, or with `None` for browsers without
+    /// profiles.
+    choices: Vec<(InstalledBrowser, Option<Profile>)>,
+    pub state: ListState,
+}
+
+impl OpenWithMenu {
+    /// Lists every profile of every installed browser, selecting the one the bookmark was
+    /// imported from.
+    fn new(browsers: &[InstalledBrowser], bookmark: &Bookmark) -> Self {
+        let mut choices: Vec<(InstalledBrowser, Option<Profile>)> = Vec::new();
+        for browser in browsers {
+            if browser.profiles.is_empty() {
+                choices.push((browser.clone(), None));
+            }
+            for profile in &browser.profiles {
+                choices.push((browser.clone(), Some(profile.clone())));
+            }
+        }
+
+        let origin = InstalledBrowser::for_bookmark(browsers, bookmark);
+        let selected = origin.and_then(|(browser, profile)| {
+            choices.iter().position(|(b, p)| {
+                b.browser == browser.browser
+                    && (profile.is_none()
+                        || p.as_ref().map(|p| &p.path) == profile.map(|p| &p.path))
+            })
+        });
+        OpenWithMenu {
+            url: bookmark.url.clone(),
+            choices,
+            state: ListState::default().with_selected(Some(selected.unwrap_or(0))),
+        }
+    }
+
+    /// Returns the labels of the choices, like `Google Chrome (Work)`.
+    pub fn labels(&self) -> Vec<String> {
+        self.choices
+            .iter()
+            .map(|(browser, profile)| Self::label(browser, profile.as_ref()))
+            .collect()
+    }
+
+    fn label(browser: &InstalledBrowser, profile: Option<&Profile>) -> String {
+        match profile {
+            Some(profile) => format!("{} ({})", browser.app.name, profile.name),
+            None => browser.app.name.clone(),
+        }
+    }
+
+    fn selected(&self) -> Option<(&InstalledBrowser, Option<&Profile>)> {
+        let (browser, profile) = self.choices.get(self.state.selected()?)?;
+        Some((browser, profile.as_ref()))
+    }
+}
+
+/// Menu of the actions for a bookmark or app.
+pub struct ActionMenu {
+    /// Name of the bookmark or app the actions apply to.
+    pub title: String,
+    actions: Vec<Action>,
+    pub state: ListState,
+}
+
+impl ActionMenu {
+    fn new(title: &str, actions: &[Action]) -> Self {
+        ActionMenu {
+            title: title.to_string(),
+            actions: actions.to_vec(),
+            state: ListState::default().with_selected(Some(0)),
+        }
+    }
+
+    pub fn labels(&self) -> Vec<&'static str> {
+        self.actions.iter().map(Action::label).collect()
+    }
+
+    fn selected(&self) -> Option<Action> {
+        self.actions.get(self.state.selected()?).copied()
+    }
+}
+
+pub struct HistoryList {
+    entries: Vec<HistoryEntry>,
     pub state: ListState,
 }
 
@@ -234,3 +834,10 @@ impl ConfigManager {
         Ok(())
     }
 }
+
+/// Returns the current time in seconds since the Unix epoch.
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs() as i64)
+}
@@ -0,0 +1,79 @@
+//! Bookmark Dates
+//!
+//! This module converts between seconds since the Unix epoch, as dates are kept in a
+//! [`Bookmark`](super::Bookmark), and ISO 8601 timestamps in UTC like `2023-10-31T12:00:00Z`, as
+//! they are written to XBEL files and shown in the UI. The calendar conversions follow Howard
+//! Hinnant's `civil_from_days` and `days_from_civil` algorithms for the proleptic Gregorian
+//! calendar.
+
+/// Formats seconds since the Unix epoch as an ISO 8601 timestamp in UTC, like
+/// `2023-10-31T12:00:00Z`.
+pub(crate) fn to_iso8601(timestamp: i64) -> String {
+    let (days, seconds) = (timestamp.div_euclid(86_400), timestamp.rem_euclid(86_400));
+
+    // Convert days since the epoch to a civil date.
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let doe = days.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        seconds / 3_600,
+        seconds % 3_600 / 60,
+        seconds % 60
+    )
+}
+
+/// Parses the date and time of an ISO 8601 timestamp, like `2023-10-31T12:00:00Z`, as UTC. A
+/// missing time is read as midnight.
+///
+/// # Returns
+/// - `Some(i64)` with the seconds since the Unix epoch.
+/// - `None` if the timestamp cannot be parsed.
+pub(crate) fn from_iso8601(timestamp: &str) -> Option<i64> {
+    let number = |range: std::ops::Range<usize>| -> Option<i64> {
+        timestamp.get(range).and_then(|s| s.parse().ok())
+    };
+    let (year, month, day) = (number(0..4)?, number(5..7)?, number(8..10)?);
+    let (hour, minute, second) = (
+        number(11..13).unwrap_or(0),
+        number(14..16).unwrap_or(0),
+        number(17..19).unwrap_or(0),
+    );
+
+    // Convert the civil date to days since the epoch.
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let doy = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146_097 + doe - 719_468;
+
+    Some(days * 86_400 + hour * 3_600 + minute * 60 + second)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_iso8601_round_trip() {
+        for (timestamp, iso) in [
+            (0, "1970-01-01T00:00:00Z"),
+            (1_698_710_400, "2023-10-31T00:00:00Z"),
+            (951_827_696, "2000-02-29T12:34:56Z"),
+            (-86_400, "1969-12-31T00:00:00Z"),
+        ] {
+            assert_eq!(to_iso8601(timestamp), iso);
+            assert_eq!(from_iso8601(iso), Some(timestamp));
+        }
+        assert_eq!(from_iso8601("2023-10-31"), Some(1_698_710_400));
+        assert_eq!(from_iso8601("yesterday"), None);
+    }
+}
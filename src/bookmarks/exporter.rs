@@ -0,0 +1,198 @@
+//! Bookmarks Exporter
+//!
+//! This module writes bookmarks to bookmark file formats that other browsers and tools can
+//! import, delegating the actual formatting to format-specific submodules.
+//!
+//! # Supported Formats
+//! - Netscape bookmark HTML (`NETSCAPE-Bookmark-file-1`), importable by every major browser
+//! - XBEL (XML Bookmark Exchange Language), importable by Konqueror, Falkon and bookmark tools
+//! - JSON, an array of objects with the name, URL, folder and sources of each bookmark
+//! - CSV, with the same columns as JSON
+//! - Markdown, a link list with a heading per folder
+//!
+//! # Usage
+//! Use [`export_to`] with an [`ExportFormat`] to write bookmarks to any [`Write`] target, or
+//! [`format_link`] with a [`LinkFormat`] to format a single bookmark as a link.
+
+use std::{io::Write, str::FromStr};
+
+use anyhow::Result;
+
+use super::Bookmark;
+
+mod csv;
+mod json;
+mod markdown;
+mod netscape;
+mod xbel;
+
+/// Enum representing supported formats for bookmark export.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum ExportFormat {
+    Html,
+    Xbel,
+    Json,
+    Csv,
+    Markdown,
+}
+
+impl FromStr for ExportFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "html" => Ok(ExportFormat::Html),
+            "xbel" => Ok(ExportFormat::Xbel),
+            "json" => Ok(ExportFormat::Json),
+            "csv" => Ok(ExportFormat::Csv),
+            "md" | "markdown" => Ok(ExportFormat::Markdown),
+            _ => Err(anyhow::anyhow!(
+                "Unknown export format {:?}. Supported formats: html, xbel, json, csv, markdown",
+                s
+            )),
+        }
+    }
+}
+
+/// Writes bookmarks in the specified format.
+///
+/// # Arguments
+/// * `bookmarks` - The bookmarks to export.
+/// * `format` - The format to write.
+/// * `writer` - Where to write the exported bookmarks to.
+///
+/// # Returns
+/// * `Ok(())` on success.
+/// * `Err(anyhow::Error)` if writing fails.
+pub fn export_to(
+    bookmarks: &[Bookmark],
+    format: ExportFormat,
+    writer: &mut impl Write,
+) -> Result<()> {
+    match format {
+        ExportFormat::Html => netscape::export(bookmarks, writer),
+        ExportFormat::Xbel => xbel::export(bookmarks, writer),
+        ExportFormat::Json => json::export(bookmarks, writer),
+        ExportFormat::Csv => csv::export(bookmarks, writer),
+        ExportFormat::Markdown => markdown::export(bookmarks, writer),
+    }
+}
+
+/// Markup formats for a link to a single bookmark.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum LinkFormat {
+    Markdown,
+    Org,
+    Html,
+}
+
+/// Formats a bookmark as a link, like `[Rust](https://www.rust-lang.org)` in Markdown.
+pub fn format_link(bookmark: &Bookmark, format: LinkFormat) -> String {
+    match format {
+        LinkFormat::Markdown => format!(
+            "[{}]({})",
+            markdown::escape_text(&bookmark.name),
+            markdown::escape_url(&bookmark.url)
+        ),
+        // Org mode ends links at `]]`, so brackets in the URL are escaped and the ones in the
+        // description, which cannot be escaped, are replaced.
+        LinkFormat::Org => format!(
+            "[[{}][{}]]",
+            bookmark
+                .url
+                .replace('\\', "\\\\")
+                .replace('[', "\\[")
+                .replace(']', "\\]"),
+            bookmark.name.replace('[', "{").replace(']', "}")
+        ),
+        LinkFormat::Html => format!(
+            "<a href=\"{}\">{}</a>",
+            escape_markup(&bookmark.url),
+            escape_markup(&bookmark.name)
+        ),
+    }
+}
+
+/// A folder of bookmarks, rebuilt from the folder paths of a flat bookmark list.
+///
+/// Used by formats that nest bookmarks inside folders. Folders and bookmarks keep the order in
+/// which they first appear in the list.
+struct FolderTree<'a> {
+    name: &'a str,
+    bookmarks: Vec<&'a Bookmark>,
+    children: Vec<FolderTree<'a>>,
+}
+
+impl<'a> FolderTree<'a> {
+    /// Builds the folder tree of the given bookmarks. The returned root folder has no name.
+    fn build(bookmarks: &'a [Bookmark]) -> FolderTree<'a> {
+        let mut root = FolderTree::new("");
+        for bookmark in bookmarks {
+            let mut folder = &mut root;
+            for name in bookmark.folder.split('/').filter(|n| !n.is_empty()) {
+                folder = folder.child(name);
+            }
+            folder.bookmarks.push(bookmark);
+        }
+        root
+    }
+
+    fn new(name: &'a str) -> FolderTree<'a> {
+        FolderTree {
+            name,
+            bookmarks: Vec::new(),
+            children: Vec::new(),
+        }
+    }
+
+    /// Returns the child folder with the given name, creating it if needed.
+    fn child(&mut self, name: &'a str) -> &mut FolderTree<'a> {
+        let index = match self.children.iter().position(|c| c.name == name) {
+            Some(index) => index,
+            None => {
+                self.children.push(FolderTree::new(name));
+                self.children.len() - 1
+            }
+        };
+        &mut self.children[index]
+    }
+}
+
+/// Returns the sources a bookmark was imported from, like `Chrome (Default)`.
+fn sources(bookmark: &Bookmark) -> Vec<String> {
+    bookmark.origins.iter().map(ToString::to_string).collect()
+}
+
+/// Escapes the characters that have a special meaning in HTML and XML.
+fn escape_markup(input: &str) -> String {
+    input
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_link() {
+        let bookmark = Bookmark::new(
+            "Grafana [prod] & <co>",
+            "https://grafana.example.com/(a)[b]",
+        );
+        assert_eq!(
+            format_link(&bookmark, LinkFormat::Markdown),
+            r"[Grafana \[prod\] & \<co\>](https://grafana.example.com/%28a%29[b])"
+        );
+        assert_eq!(
+            format_link(&bookmark, LinkFormat::Org),
+            r"[[https://grafana.example.com/(a)\[b\]][Grafana {prod} & <co>]]"
+        );
+        assert_eq!(
+            format_link(&bookmark, LinkFormat::Html),
+            "<a href=\"https://grafana.example.com/(a)[b]\">Grafana [prod] &amp; &lt;co&gt;</a>"
+        );
+    }
+}
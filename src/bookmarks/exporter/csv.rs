@@ -0,0 +1,71 @@
+//! CSV Exporter
+//!
+//! This module writes bookmarks as a CSV file following RFC 4180, with a header row and the
+//! columns `name`, `url`, `folder` and `sources`. A bookmark imported from several sources lists
+//! them separated by `; `.
+
+use std::io::Write;
+
+use anyhow::Result;
+
+use super::sources;
+use crate::bookmarks::Bookmark;
+
+const HEADER: &str = "name,url,folder,sources";
+
+/// Writes bookmarks as a CSV file.
+///
+/// # Arguments
+/// - `bookmarks`: The bookmarks to export.
+/// - `writer`: Where to write the file content to.
+///
+/// # Returns
+/// - `Ok(())` on success.
+/// - `Err(anyhow::Error)` if writing fails.
+pub(crate) fn export(bookmarks: &[Bookmark], writer: &mut impl Write) -> Result<()> {
+    write!(writer, "{HEADER}\r\n")?;
+    for bookmark in bookmarks {
+        let fields = [
+            escape_field(&bookmark.name),
+            escape_field(&bookmark.url),
+            escape_field(&bookmark.folder),
+            escape_field(&sources(bookmark).join("; ")),
+        ];
+        write!(writer, "{}\r\n", fields.join(","))?;
+    }
+    Ok(())
+}
+
+/// Quotes a field if it contains a separator, quote or line break, doubling any quotes.
+fn escape_field(field: &str) -> String {
+    if field.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bookmarks::importer::{Browser, Origin};
+
+    #[test]
+    fn test_export_quotes_fields() {
+        let mut grafana = Bookmark::new("Grafana, \"prod\"", "https://grafana.example.com");
+        grafana.folder = "Work/Infra".to_string();
+        grafana.origins = vec![
+            Origin::new(Browser::Chrome, Some("Default")),
+            Origin::new(Browser::Firefox, None),
+        ];
+
+        let mut output: Vec<u8> = Vec::new();
+        export(&[grafana], &mut output).unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "name,url,folder,sources\r\n\
+             \"Grafana, \"\"prod\"\"\",https://grafana.example.com,Work/Infra,Chrome (Default); Firefox\r\n"
+        );
+    }
+}
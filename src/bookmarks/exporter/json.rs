@@ -0,0 +1,72 @@
+//! JSON Exporter
+//!
+//! This module writes bookmarks as a pretty-printed JSON array for scripts. Every bookmark is an
+//! object with its `name`, `url`, `folder` and the `sources` it was imported from.
+
+use std::io::Write;
+
+use anyhow::Result;
+use serde::Serialize;
+
+use super::sources;
+use crate::bookmarks::Bookmark;
+
+/// A bookmark as written to the JSON file.
+#[derive(Serialize)]
+struct Record<'a> {
+    name: &'a str,
+    url: &'a str,
+    folder: &'a str,
+    sources: Vec<String>,
+}
+
+/// Writes bookmarks as a JSON array.
+///
+/// # Arguments
+/// - `bookmarks`: The bookmarks to export.
+/// - `writer`: Where to write the file content to.
+///
+/// # Returns
+/// - `Ok(())` on success.
+/// - `Err(anyhow::Error)` if writing fails.
+pub(crate) fn export(bookmarks: &[Bookmark], writer: &mut impl Write) -> Result<()> {
+    let records: Vec<Record> = bookmarks
+        .iter()
+        .map(|bookmark| Record {
+            name: &bookmark.name,
+            url: &bookmark.url,
+            folder: &bookmark.folder,
+            sources: sources(bookmark),
+        })
+        .collect();
+    serde_json::to_writer_pretty(&mut *writer, &records)?;
+    writeln!(writer)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bookmarks::importer::{Browser, Origin};
+
+    #[test]
+    fn test_export_records() {
+        let mut rust = Bookmark::new("Rust", "https://www.rust-lang.org");
+        rust.folder = "Lang".to_string();
+        rust.origins = vec![Origin::new(Browser::Firefox, Some("default"))];
+
+        let mut output: Vec<u8> = Vec::new();
+        export(&[rust], &mut output).unwrap();
+        let value: serde_json::Value = serde_json::from_slice(&output).unwrap();
+
+        assert_eq!(
+            value,
+            serde_json::json!([{
+                "name": "Rust",
+                "url": "https://www.rust-lang.org",
+                "folder": "Lang",
+                "sources": ["Firefox (default)"],
+            }])
+        );
+    }
+}
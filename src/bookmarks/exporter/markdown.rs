@@ -0,0 +1,109 @@
+//! Markdown Exporter
+//!
+//! This module writes bookmarks as a Markdown link list, e.g. for a wiki page. Bookmarks are
+//! grouped under a heading per folder, with the full folder path as heading text, and each link
+//! is followed by the sources it was imported from.
+
+use std::io::Write;
+
+use anyhow::Result;
+
+use super::{sources, FolderTree};
+use crate::bookmarks::Bookmark;
+
+/// Writes bookmarks as a Markdown link list.
+///
+/// # Arguments
+/// - `bookmarks`: The bookmarks to export.
+/// - `writer`: Where to write the file content to.
+///
+/// # Returns
+/// - `Ok(())` on success.
+/// - `Err(anyhow::Error)` if writing fails.
+pub(crate) fn export(bookmarks: &[Bookmark], writer: &mut impl Write) -> Result<()> {
+    writeln!(writer, "# Bookmarks")?;
+    write_folder(&FolderTree::build(bookmarks), "", writer)?;
+    Ok(())
+}
+
+/// Recursively writes the bookmarks of a folder, followed by its subfolders.
+fn write_folder(folder: &FolderTree, path: &str, writer: &mut impl Write) -> Result<()> {
+    if !folder.bookmarks.is_empty() {
+        writeln!(writer)?;
+        if !path.is_empty() {
+            writeln!(writer, "## {}", escape_text(path))?;
+            writeln!(writer)?;
+        }
+    }
+
+    for bookmark in &folder.bookmarks {
+        write!(
+            writer,
+            "- [{}]({})",
+            escape_text(&bookmark.name),
+            escape_url(&bookmark.url)
+        )?;
+        let sources = sources(bookmark);
+        if !sources.is_empty() {
+            write!(writer, " ({})", escape_text(&sources.join(", ")))?;
+        }
+        writeln!(writer)?;
+    }
+
+    for child in &folder.children {
+        let child_path = if path.is_empty() {
+            child.name.to_string()
+        } else {
+            format!("{path}/{}", child.name)
+        };
+        write_folder(child, &child_path, writer)?;
+    }
+    Ok(())
+}
+
+/// Escapes the characters that Markdown would interpret as formatting in link text or headings.
+pub(super) fn escape_text(input: &str) -> String {
+    let mut escaped = String::with_capacity(input.len());
+    for c in input.chars() {
+        if matches!(c, '\\' | '[' | ']' | '*' | '_' | '`' | '<' | '>' | '#') {
+            escaped.push('\\');
+        }
+        escaped.push(if c == '\n' { ' ' } else { c });
+    }
+    escaped
+}
+
+/// Percent-encodes the characters that would end a Markdown link destination early.
+pub(super) fn escape_url(input: &str) -> String {
+    input
+        .replace(' ', "%20")
+        .replace('(', "%28")
+        .replace(')', "%29")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bookmarks::importer::{Browser, Origin};
+
+    #[test]
+    fn test_export_groups_by_folder() {
+        let mut grafana = Bookmark::new("Grafana [prod]", "https://grafana.example.com/(a)");
+        grafana.folder = "Work/Infra".to_string();
+        grafana.origins = vec![Origin::new(Browser::Chrome, Some("Default"))];
+        let rust = Bookmark::new("Rust", "https://www.rust-lang.org");
+
+        let mut output: Vec<u8> = Vec::new();
+        export(&[grafana, rust], &mut output).unwrap();
+
+        let expected = "# Bookmarks
+
+- [Rust](https://www.rust-lang.org)
+
+## Work/Infra
+
+- [Grafana \\[prod\\]](https://grafana.example.com/%28a%29) (Chrome (Default))
+";
+        assert_eq!(String::from_utf8(output).unwrap(), expected);
+    }
+}
@@ -0,0 +1,109 @@
+//! Netscape Bookmark File Exporter
+//!
+//! This module writes bookmarks as an HTML file in the `NETSCAPE-Bookmark-file-1` format, which
+//! every major browser can import. Folder paths are written as nested `<H3>`/`<DL>` folders, and
+//! the `ADD_DATE`, `TAGS` and `SHORTCUTURL` attributes are written when known.
+
+use std::io::Write;
+
+use anyhow::Result;
+
+use super::{escape_markup, FolderTree};
+use crate::bookmarks::Bookmark;
+
+const HEADER: &str = r#"<!DOCTYPE NETSCAPE-Bookmark-file-1>
+<!-- This is an automatically generated file.
+     It will be read and overwritten.
+     DO NOT EDIT! -->
+<META HTTP-EQUIV="Content-Type" CONTENT="text/html; charset=UTF-8">
+<TITLE>Bookmarks</TITLE>
+<H1>Bookmarks</H1>
+"#;
+
+/// Writes bookmarks as a Netscape bookmark file.
+///
+/// # Arguments
+/// - `bookmarks`: The bookmarks to export.
+/// - `writer`: Where to write the file content to.
+///
+/// # Returns
+/// - `Ok(())` on success.
+/// - `Err(anyhow::Error)` if writing fails.
+pub(crate) fn export(bookmarks: &[Bookmark], writer: &mut impl Write) -> Result<()> {
+    writer.write_all(HEADER.as_bytes())?;
+    write_folder(&FolderTree::build(bookmarks), 0, writer)?;
+    Ok(())
+}
+
+/// Recursively writes the content of a folder as a `<DL>` list.
+fn write_folder(folder: &FolderTree, depth: usize, writer: &mut impl Write) -> Result<()> {
+    let indent = "    ".repeat(depth);
+    writeln!(writer, "{indent}<DL><p>")?;
+
+    for child in &folder.children {
+        writeln!(
+            writer,
+            "{indent}    <DT><H3>{}</H3>",
+            escape_markup(child.name)
+        )?;
+        write_folder(child, depth + 1, writer)?;
+    }
+
+    for bookmark in &folder.bookmarks {
+        let mut attributes = format!("HREF=\"{}\"", escape_markup(&bookmark.url));
+        if let Some(date_added) = bookmark.date_added {
+            attributes.push_str(&format!(" ADD_DATE=\"{date_added}\""));
+        }
+        if !bookmark.tags.is_empty() {
+            attributes.push_str(&format!(
+                " TAGS=\"{}\"",
+                escape_markup(&bookmark.tags.join(","))
+            ));
+        }
+        if let Some(keyword) = &bookmark.keyword {
+            attributes.push_str(&format!(" SHORTCUTURL=\"{}\"", escape_markup(keyword)));
+        }
+        writeln!(
+            writer,
+            "{indent}    <DT><A {attributes}>{}</A>",
+            escape_markup(&bookmark.name)
+        )?;
+    }
+
+    writeln!(writer, "{indent}</DL><p>")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_export_nests_folders() {
+        let mut grafana = Bookmark::new("Grafana & Co", "https://grafana.example.com/?a=1&b=2");
+        grafana.folder = "Work/Infra".to_string();
+        grafana.tags = vec!["oncall".to_string()];
+        let mut jira = Bookmark::new("Jira", "https://jira.example.com");
+        jira.folder = "Work".to_string();
+        let rust = Bookmark::new("Rust", "https://www.rust-lang.org");
+
+        let mut output: Vec<u8> = Vec::new();
+        export(&[grafana, jira, rust], &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        let expected = r#"<DL><p>
+    <DT><H3>Work</H3>
+    <DL><p>
+        <DT><H3>Infra</H3>
+        <DL><p>
+            <DT><A HREF="https://grafana.example.com/?a=1&amp;b=2" TAGS="oncall">Grafana &amp; Co</A>
+        </DL><p>
+        <DT><A HREF="https://jira.example.com">Jira</A>
+    </DL><p>
+    <DT><A HREF="https://www.rust-lang.org">Rust</A>
+</DL><p>
+"#;
+        assert!(output.starts_with("<!DOCTYPE NETSCAPE-Bookmark-file-1>"));
+        assert!(output.ends_with(expected));
+    }
+}
@@ -0,0 +1,113 @@
+//! XBEL Exporter
+//!
+//! This module writes bookmarks as an XBEL file, the XML Bookmark Exchange Language understood by
+//! Konqueror, Falkon and many bookmark tools. Folder paths are written as nested `<folder>`
+//! elements. The `id` and `added` attributes and the `<desc>` element are written when known.
+
+use std::io::Write;
+
+use anyhow::Result;
+
+use super::{escape_markup, FolderTree};
+use crate::bookmarks::{to_iso8601, Bookmark};
+
+const HEADER: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE xbel PUBLIC "+//IDN python.org//DTD XML Bookmark Exchange Language 1.0//EN//XML" "http://pyxml.sourceforge.net/topics/dtds/xbel.dtd">
+<xbel version="1.0">
+"#;
+
+/// Writes bookmarks as an XBEL file.
+///
+/// # Arguments
+/// - `bookmarks`: The bookmarks to export.
+/// - `writer`: Where to write the file content to.
+///
+/// # Returns
+/// - `Ok(())` on success.
+/// - `Err(anyhow::Error)` if writing fails.
+pub(crate) fn export(bookmarks: &[Bookmark], writer: &mut impl Write) -> Result<()> {
+    writer.write_all(HEADER.as_bytes())?;
+    write_folder(&FolderTree::build(bookmarks), 1, writer)?;
+    writeln!(writer, "</xbel>")?;
+    Ok(())
+}
+
+/// Recursively writes the subfolders and bookmarks of a folder.
+fn write_folder(folder: &FolderTree, depth: usize, writer: &mut impl Write) -> Result<()> {
+    let indent = "    ".repeat(depth);
+
+    for child in &folder.children {
+        writeln!(writer, "{indent}<folder>")?;
+        writeln!(
+            writer,
+            "{indent}    <title>{}</title>",
+            escape_markup(child.name)
+        )?;
+        write_folder(child, depth + 1, writer)?;
+        writeln!(writer, "{indent}</folder>")?;
+    }
+
+    for bookmark in &folder.bookmarks {
+        let mut attributes = format!("href=\"{}\"", escape_markup(&bookmark.url));
+        if let Some(guid) = &bookmark.guid {
+            attributes.push_str(&format!(" id=\"{}\"", escape_markup(guid)));
+        }
+        if let Some(date_added) = bookmark.date_added {
+            attributes.push_str(&format!(" added=\"{}\"", to_iso8601(date_added)));
+        }
+        writeln!(writer, "{indent}<bookmark {attributes}>")?;
+        writeln!(
+            writer,
+            "{indent}    <title>{}</title>",
+            escape_markup(&bookmark.name)
+        )?;
+        if let Some(description) = bookmark.description.as_deref().filter(|d| !d.is_empty()) {
+            writeln!(
+                writer,
+                "{indent}    <desc>{}</desc>",
+                escape_markup(description)
+            )?;
+        }
+        writeln!(writer, "{indent}</bookmark>")?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_export_nests_folders() {
+        let mut grafana = Bookmark::new("Grafana & Co", "https://grafana.example.com/?a=1&b=2");
+        grafana.folder = "Work/Infra".to_string();
+        grafana.guid = Some("42".to_string());
+        grafana.date_added = Some(1_698_753_600);
+        grafana.description = Some("Dashboards".to_string());
+        let rust = Bookmark::new("Rust", "https://www.rust-lang.org");
+
+        let mut output: Vec<u8> = Vec::new();
+        export(&[grafana, rust], &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        let expected = r#"<xbel version="1.0">
+    <folder>
+        <title>Work</title>
+        <folder>
+            <title>Infra</title>
+            <bookmark href="https://grafana.example.com/?a=1&amp;b=2" id="42" added="2023-10-31T12:00:00Z">
+                <title>Grafana &amp; Co</title>
+                <desc>Dashboards</desc>
+            </bookmark>
+        </folder>
+    </folder>
+    <bookmark href="https://www.rust-lang.org">
+        <title>Rust</title>
+    </bookmark>
+</xbel>
+"#;
+        assert!(output.starts_with("<?xml version=\"1.0\""));
+        assert!(output.ends_with(expected));
+    }
+}
@@ -1,15 +1,20 @@
 //! Bookmarks Importer
 //!
 //! This module provides a unified interface for importing bookmarks from different browsers.
-//! It currently supports Google Chrome and Mozilla Firefox, delegating the actual import logic
-//! to browser-specific submodules.
+//! It currently supports the Chromium and Firefox families of browsers and bookmark files,
+//! delegating the actual import logic to browser-specific submodules.
 //!
 //! # Supported Browsers
-//! - Google Chrome
-//! - Mozilla Firefox
+//! - Google Chrome, Chromium, Brave, Microsoft Edge, Vivaldi and Opera
+//! - Mozilla Firefox, LibreWolf, Waterfox, Floorp and Zen
+//! - qutebrowser, GNOME Web, Falkon and Konqueror
+//! - Netscape bookmark HTML files, as exported by every major browser
+//! - XBEL files, the XML Bookmark Exchange Language
+//! - The buku bookmark manager
 //!
 //! # Usage
-//! Use [`import_from`] with a [`Browser`] variant to import bookmarks from the selected browser.
+//! Use [`import_from`] with a [`Source`] to import bookmarks and history from the selected
+//! browser, or [`import_all`] to import from several sources into deduplicated lists.
 //!
 //! # Errors
 //! Returns an error if bookmarks cannot be imported from the selected browser, for example if
@@ -17,15 +22,207 @@
 
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
+use std::{fmt, path::PathBuf};
 
+use super::{
+    merge,
+    search::{self, SearchEngine},
+};
+
+mod buku;
 mod chrome;
+mod epiphany;
+mod falkon;
 mod firefox;
+mod gvdb;
+mod netscape;
+mod profile;
+mod qutebrowser;
+mod snapshot;
+mod xbel;
+
+pub(crate) use profile::Profile;
 
 /// Enum representing supported browsers for bookmark import.
-#[derive(Serialize, Deserialize, PartialEq, Debug)]
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Copy)]
 pub enum Browser {
     Chrome,
+    Chromium,
+    Brave,
+    Edge,
+    Vivaldi,
+    Opera,
     Firefox,
+    LibreWolf,
+    Waterfox,
+    Floorp,
+    Zen,
+    Qutebrowser,
+    /// GNOME Web, also known as Epiphany.
+    GnomeWeb,
+    Falkon,
+    Konqueror,
+    /// A Netscape bookmark HTML file, read from the path of the [`Source`].
+    Html,
+    /// An XBEL file, read from the path of the [`Source`].
+    Xbel,
+    /// The buku bookmark manager, read from its default database or the path of the [`Source`].
+    Buku,
+    /// hhand's own bookmark store, which is always loaded and cannot be configured as a source.
+    Hhand,
+}
+
+impl Browser {
+    /// Lists the profiles of the browser, for opening bookmarks in a chosen profile.
+    ///
+    /// # Returns
+    /// - `Ok(Vec<Profile>)` with the default profile first, empty for browsers without profiles.
+    /// - `Err(anyhow::Error)` if the profile directories cannot be determined.
+    pub(crate) fn list_profiles(&self) -> Result<Vec<Profile>> {
+        match self {
+            Browser::Chrome
+            | Browser::Chromium
+            | Browser::Brave
+            | Browser::Edge
+            | Browser::Vivaldi
+            | Browser::Opera => chrome::list_profiles(self),
+            Browser::Firefox
+            | Browser::LibreWolf
+            | Browser::Waterfox
+            | Browser::Floorp
+            | Browser::Zen => firefox::list_profiles(self),
+            Browser::Falkon => falkon::list_profiles(),
+            _ => Ok(Vec::new()),
+        }
+    }
+}
+
+/// Value of a profile selection that selects every profile of a browser.
+pub const ALL_PROFILES: &str = "*";
+
+/// A configured place to import bookmarks from.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct Source {
+    pub browser: Browser,
+    /// Browser profiles to import from, by display name or directory name.
+    /// Leave empty for the default profile, or use [`ALL_PROFILES`] for all profiles.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub profiles: Vec<String>,
+    /// Path of the bookmark file for file-based sources like [`Browser::Html`], [`Browser::Xbel`]
+    /// and [`Browser::Buku`]. Optional for buku, which has a default database.
+    /// A leading `~` is expanded to the home directory.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathBuf>,
+}
+
+impl Source {
+    /// Creates a new [`Source`] for the default profile of the given browser.
+    pub fn new(browser: Browser) -> Source {
+        Source {
+            browser,
+            profiles: Vec::new(),
+            path: None,
+        }
+    }
+
+    /// Returns the bookmark files that importing from this source reads, for detecting changes.
+    ///
+    /// # Returns
+    /// - `Ok(Vec<PathBuf>)` with the paths of the files, which do not all need to exist.
+    /// - `Err(anyhow::Error)` if the browser or its profiles cannot be found.
+    pub(crate) fn files(&self) -> Result<Vec<PathBuf>> {
+        match self.browser {
+            Browser::Chrome
+            | Browser::Chromium
+            | Browser::Brave
+            | Browser::Edge
+            | Browser::Vivaldi
+            | Browser::Opera => chrome::source_files(&self.browser, &self.profiles),
+            Browser::Firefox
+            | Browser::LibreWolf
+            | Browser::Waterfox
+            | Browser::Floorp
+            | Browser::Zen => firefox::source_files(&self.browser, &self.profiles),
+            Browser::Qutebrowser => Ok(qutebrowser::source_files()?.to_vec()),
+            Browser::GnomeWeb => Ok(vec![epiphany::bookmarks_path()?]),
+            Browser::Falkon => falkon::source_files(&self.profiles),
+            Browser::Konqueror => Ok(vec![xbel::konqueror_path()?]),
+            Browser::Html | Browser::Xbel => Ok(vec![self.file_path()?]),
+            Browser::Buku => {
+                let path = buku::database_path(self)?;
+                let wal_path = PathBuf::from(format!("{}-wal", path.display()));
+                Ok(vec![path, wal_path])
+            }
+            Browser::Hhand => anyhow::bail!("hhand bookmarks are always loaded"),
+        }
+    }
+
+    /// Returns the files besides the bookmarks that importing from this source reads, like
+    /// the history of Chromium-based browsers. Firefox keeps its history in the same database
+    /// as the bookmarks, so only Chromium-based browsers have any.
+    ///
+    /// # Returns
+    /// - `Ok(Vec<PathBuf>)` with the paths of the files, which do not all need to exist.
+    /// - `Err(anyhow::Error)` if the browser or its profiles cannot be found.
+    pub(crate) fn history_files(&self) -> Result<Vec<PathBuf>> {
+        match self.browser {
+            Browser::Chrome
+            | Browser::Chromium
+            | Browser::Brave
+            | Browser::Edge
+            | Browser::Vivaldi
+            | Browser::Opera => chrome::history_files(&self.browser, &self.profiles),
+            _ => Ok(Vec::new()),
+        }
+    }
+
+    /// Returns the configured file path with a leading `~` expanded to the home directory.
+    ///
+    /// # Returns
+    /// - `Ok(PathBuf)` with the expanded path.
+    /// - `Err(anyhow::Error)` if no path is configured or the home directory cannot be determined.
+    fn file_path(&self) -> Result<PathBuf> {
+        let path = self
+            .path
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("{:?} sources need a path", self.browser))?;
+
+        match path.strip_prefix("~") {
+            Ok(relative) => {
+                let home_dir = dirs::home_dir()
+                    .ok_or_else(|| anyhow::anyhow!("Could not determine home directory."))?;
+                Ok(home_dir.join(relative))
+            }
+            Err(_) => Ok(path.clone()),
+        }
+    }
+}
+
+/// Where an imported bookmark came from.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct Origin {
+    pub browser: Browser,
+    /// Name of the browser profile, if the browser has profiles.
+    pub profile: Option<String>,
+}
+
+impl Origin {
+    /// Creates a new [`Origin`] for the given browser and profile.
+    pub fn new(browser: Browser, profile: Option<&str>) -> Origin {
+        Origin {
+            browser,
+            profile: profile.map(str::to_string),
+        }
+    }
+}
+
+impl fmt::Display for Origin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.profile {
+            Some(profile) => write!(f, "{:?} ({})", self.browser, profile),
+            None => write!(f, "{:?}", self.browser),
+        }
+    }
 }
 
 /// Represents a single bookmark with a name and URL.
@@ -33,6 +230,28 @@ pub enum Browser {
 pub struct Bookmark {
     pub name: String,
     pub url: String,
+    /// Path of the folder containing the bookmark, with folder names separated by `/`,
+    /// e.g. `Bookmarks Toolbar/Work/Infra`. Empty if the bookmark is not in a folder.
+    pub folder: String,
+    /// Tags attached to the bookmark.
+    pub tags: Vec<String>,
+    /// Keyword that opens the bookmark when typed into the browser's address bar.
+    pub keyword: Option<String>,
+    /// Longer description of the bookmarked page.
+    pub description: Option<String>,
+    /// When the bookmark was added, in seconds since the Unix epoch.
+    pub date_added: Option<i64>,
+    /// When the bookmark was last opened or visited, in seconds since the Unix epoch.
+    pub last_used: Option<i64>,
+    /// Number of times the bookmarked page was visited.
+    pub visit_count: u32,
+    /// Firefox's frecency score of the page, combining how often and how recently it was
+    /// visited. Zero when unknown.
+    pub frecency: i64,
+    /// Identifier the browser assigned to the bookmark, if it has one.
+    pub guid: Option<String>,
+    /// Every source the bookmark was imported from.
+    pub origins: Vec<Origin>,
 }
 
 impl Bookmark {
@@ -41,21 +260,275 @@ impl Bookmark {
         Bookmark {
             name: name.to_string(),
             url: url.to_string(),
+            folder: String::new(),
+            tags: Vec::new(),
+            keyword: None,
+            description: None,
+            date_added: None,
+            last_used: None,
+            visit_count: 0,
+            frecency: 0,
+            guid: None,
+            origins: Vec::new(),
+        }
+    }
+
+    /// Returns true if the bookmark is kept in hhand's own bookmark store, and can be edited.
+    pub fn is_stored(&self) -> bool {
+        self.origins.iter().any(|o| o.browser == Browser::Hhand)
+    }
+
+    /// Returns true if the name, folder, tags, keyword or description of the bookmark contain
+    /// `query`, ignoring case.
+    pub fn matches(&self, query: &str) -> bool {
+        let query = query.to_uppercase();
+        self.name.to_uppercase().contains(&query)
+            || self.folder.to_uppercase().contains(&query)
+            || self.tags.iter().any(|t| t.to_uppercase().contains(&query))
+            || [&self.keyword, &self.description]
+                .into_iter()
+                .flatten()
+                .any(|text| text.to_uppercase().contains(&query))
+    }
+
+    /// Returns a score for ranking search results, higher meaning more relevant.
+    ///
+    /// Uses Firefox's frecency when known. Otherwise a frecency-like score is estimated from the
+    /// visit count and how recently the bookmark was used, weighted the way Firefox weighs visit
+    /// age.
+    ///
+    /// # Arguments
+    /// * `now` - The current time, in seconds since the Unix epoch.
+    pub fn rank(&self, now: i64) -> i64 {
+        const DAY: i64 = 86_400;
+        let recency_weight = match self.last_used.map(|t| now - t) {
+            None => 10,
+            Some(age) if age < 4 * DAY => 100,
+            Some(age) if age < 14 * DAY => 70,
+            Some(age) if age < 31 * DAY => 50,
+            Some(age) if age < 90 * DAY => 30,
+            Some(_) => 10,
+        };
+        let visits = i64::from(self.visit_count.max(u32::from(self.last_used.is_some())));
+        self.frecency.max(visits * recency_weight)
+    }
+
+    /// Returns the sources of the bookmark as a comma-separated list.
+    pub fn origins_label(&self) -> String {
+        self.origins
+            .iter()
+            .map(|o| o.to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// A page from the browsing history.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct HistoryEntry {
+    pub title: String,
+    pub url: String,
+    /// Time of the most recent visit, in seconds since the Unix epoch.
+    pub last_visit: Option<i64>,
+    /// Number of times the page was visited.
+    pub visit_count: u32,
+    /// Every source the entry was imported from.
+    pub origins: Vec<Origin>,
+}
+
+impl HistoryEntry {
+    /// Creates a new [`HistoryEntry`] with the given title and URL.
+    pub fn new(title: &str, url: &str) -> HistoryEntry {
+        HistoryEntry {
+            title: title.to_string(),
+            url: url.to_string(),
+            last_visit: None,
+            visit_count: 0,
+            origins: Vec::new(),
+        }
+    }
+
+    /// Returns true if the title or URL of the entry contain `query`, ignoring case.
+    pub fn matches(&self, query: &str) -> bool {
+        let query = query.to_uppercase();
+        self.title.to_uppercase().contains(&query) || self.url.to_uppercase().contains(&query)
+    }
+}
+
+/// Bookmarks and browsing history imported from one or more sources.
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct Import {
+    pub bookmarks: Vec<Bookmark>,
+    pub history: Vec<HistoryEntry>,
+    /// An error message for every source, or part of a source, that failed to import.
+    pub errors: Vec<String>,
+    /// Number of bookmarks that were skipped because they could not be read.
+    pub skipped: usize,
+    /// Search engines, imported from browsers that keep them apart from their bookmarks.
+    #[serde(default)]
+    pub search_engines: Vec<SearchEngine>,
+}
+
+/// Imports bookmarks and history from every given source and merges them into one list each.
+///
+/// Entries found in several sources are merged by normalized URL, see [`merge::merge`] and
+/// [`merge::merge_history`]. A source that fails to import does not stop the others from being
+/// imported.
+///
+/// # Arguments
+/// * `sources` - The sources to import from.
+///
+/// # Returns
+/// * The merged [`Import`], with an error message for every source that failed to import.
+pub fn import_all(sources: &[Source]) -> Import {
+    import_unmerged(sources).merge()
+}
+
+/// Imports bookmarks and history from every given source without merging them, so that
+/// duplicates within and across sources are kept.
+///
+/// # Arguments
+/// * `sources` - The sources to import from.
+///
+/// # Returns
+/// * The [`Import`] of every source in order, with an error message for every source that failed
+///   to import.
+pub fn import_unmerged(sources: &[Source]) -> Import {
+    let mut import = Import::default();
+
+    for source in sources {
+        match import_from(source) {
+            Ok(imported) => import.append(imported),
+            Err(e) => import.errors.push(import_error(source, &e)),
+        }
+    }
+
+    import
+}
+
+impl Import {
+    /// Adds the entries of another import to this one, without merging them.
+    pub(crate) fn append(&mut self, other: Import) {
+        self.bookmarks.extend(other.bookmarks);
+        self.history.extend(other.history);
+        self.errors.extend(other.errors);
+        self.skipped += other.skipped;
+        self.search_engines.extend(other.search_engines);
+    }
+
+    /// Merges entries found in several sources by normalized URL, see [`merge::merge`] and
+    /// [`merge::merge_history`].
+    ///
+    /// Keyword bookmarks with a `%s` in their URL, as Firefox uses them for custom searches, are
+    /// added to the search engines, and search engines are merged by keyword.
+    pub(crate) fn merge(mut self) -> Import {
+        for bookmark in &self.bookmarks {
+            let Some(keyword) = &bookmark.keyword else {
+                continue;
+            };
+            if SearchEngine::is_template(&bookmark.url) {
+                let mut engine = SearchEngine::new(&bookmark.name, keyword, &bookmark.url);
+                engine.origins = bookmark.origins.clone();
+                self.search_engines.push(engine);
+            }
         }
+        self.search_engines = search::merge_search_engines(self.search_engines);
+        self.bookmarks = merge::merge(self.bookmarks);
+        self.history = merge::merge_history(self.history);
+        self
     }
 }
 
-/// Imports bookmarks from the specified browser.
+/// Formats the error message for a source that failed to import.
+pub(crate) fn import_error(source: &Source, error: &anyhow::Error) -> String {
+    format!(
+        "Failed to import bookmarks from {:?}: {}",
+        source.browser, error
+    )
+}
+
+/// Imports bookmarks, and history where available, from the specified source.
 ///
 /// # Arguments
-/// * `browser` - The browser to import bookmarks from (e.g., [`Browser::Chrome`] or [`Browser::Firefox`]).
+/// * `source` - The source to import from, naming the browser (e.g., [`Browser::Chrome`]
+///   or [`Browser::Firefox`]) and its profiles.
 ///
 /// # Returns
-/// * `Ok(Vec<Bookmark>)` containing all imported bookmarks on success.
+/// * `Ok(Import)` containing all imported bookmarks and history on success. History that cannot
+///   be read does not fail the import: a missing or locked Chrome `History` database is skipped,
+///   and other history errors are reported in [`Import::errors`].
 /// * `Err(anyhow::Error)` if bookmarks cannot be imported.
-pub fn import_from(browser: &Browser) -> Result<Vec<Bookmark>> {
+pub fn import_from(source: &Source) -> Result<Import> {
+    let browser = &source.browser;
+    let profiles = &source.profiles;
     match browser {
-        Browser::Chrome => chrome::import(),
-        Browser::Firefox => firefox::import(),
+        Browser::Chrome
+        | Browser::Chromium
+        | Browser::Brave
+        | Browser::Edge
+        | Browser::Vivaldi
+        | Browser::Opera => chrome::import(browser, profiles),
+        Browser::Firefox
+        | Browser::LibreWolf
+        | Browser::Waterfox
+        | Browser::Floorp
+        | Browser::Zen => firefox::import(browser, profiles),
+        Browser::Html => Ok(Import {
+            bookmarks: netscape::import(&source.file_path()?)?,
+            ..Import::default()
+        }),
+        Browser::Qutebrowser => Ok(Import {
+            bookmarks: qutebrowser::import()?,
+            ..Import::default()
+        }),
+        Browser::GnomeWeb => Ok(Import {
+            bookmarks: epiphany::import()?,
+            ..Import::default()
+        }),
+        Browser::Falkon => Ok(Import {
+            bookmarks: falkon::import(profiles)?,
+            ..Import::default()
+        }),
+        Browser::Konqueror => Ok(Import {
+            bookmarks: xbel::import(&xbel::konqueror_path()?, Browser::Konqueror)?,
+            ..Import::default()
+        }),
+        Browser::Xbel => Ok(Import {
+            bookmarks: xbel::import(&source.file_path()?, Browser::Xbel)?,
+            ..Import::default()
+        }),
+        Browser::Buku => Ok(Import {
+            bookmarks: buku::import(source)?,
+            ..Import::default()
+        }),
+        Browser::Hhand => anyhow::bail!("hhand bookmarks are always loaded"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: i64 = 1_700_000_000;
+    const DAY: i64 = 86_400;
+
+    #[test]
+    fn test_rank_prefers_frequent_and_recent_use() {
+        let old = Bookmark::new("Old", "https://old.example.com");
+
+        let mut daily = Bookmark::new("Daily", "https://daily.example.com");
+        daily.visit_count = 30;
+        daily.last_used = Some(NOW - DAY);
+
+        let mut stale = Bookmark::new("Stale", "https://stale.example.com");
+        stale.visit_count = 30;
+        stale.last_used = Some(NOW - 365 * DAY);
+
+        let mut firefox = Bookmark::new("Firefox", "https://firefox.example.com");
+        firefox.frecency = 5_000;
+
+        assert!(daily.rank(NOW) > stale.rank(NOW));
+        assert!(stale.rank(NOW) > old.rank(NOW));
+        assert!(firefox.rank(NOW) > daily.rank(NOW));
     }
 }
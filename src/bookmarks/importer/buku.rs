@@ -0,0 +1,131 @@
+//! buku Bookmarks Importer
+//!
+//! This module provides functionality to import bookmarks from buku, a command-line bookmark
+//! manager. buku keeps its bookmarks in a single SQLite database, by default
+//! `~/.local/share/buku/bookmarks.db` on Linux and `AppData/Roaming/buku/bookmarks.db` on
+//! Windows. A different database can be configured with the path of the [`Source`].
+//!
+//! Every bookmark has a title, URL, tags and a description. Tags are stored as one
+//! comma-separated string with leading and trailing commas, like `,rust,docs,`. buku has no
+//! folders, so imported bookmarks are not in a folder.
+//!
+//! # Errors
+//! Returns an error if the data directory cannot be determined, or if the database cannot be
+//! found or queried.
+
+use std::path::PathBuf;
+
+use anyhow::Result;
+use rusqlite::Connection;
+
+use super::{snapshot::Snapshot, Bookmark, Browser, Origin, Source};
+
+/// Path of buku's database, relative to the user's data directory.
+const DATABASE_PATH: &str = "buku/bookmarks.db";
+
+/// Imports all bookmarks from a buku database.
+///
+/// # Arguments
+/// - `source`: The source to import from, with an optional path to the database.
+///
+/// # Returns
+/// - `Ok(Vec<Bookmark>)` on success.
+/// - `Err(anyhow::Error)` if the database cannot be found or queried.
+pub(crate) fn import(source: &Source) -> Result<Vec<Bookmark>> {
+    let snapshot = Snapshot::create(&database_path(source)?)?;
+    let conn = snapshot.open()?;
+
+    let origin = Origin::new(Browser::Buku, None);
+    Ok(query_bookmarks(&conn)?
+        .into_iter()
+        .map(|mut bookmark| {
+            bookmark.origins = vec![origin.clone()];
+            bookmark
+        })
+        .collect())
+}
+
+/// Returns the path of the database to import from: the configured path, or buku's default.
+pub(crate) fn database_path(source: &Source) -> Result<PathBuf> {
+    if source.path.is_some() {
+        return source.file_path();
+    }
+    let data_dir =
+        dirs::data_dir().ok_or_else(|| anyhow::anyhow!("Could not determine data directory."))?;
+    Ok(data_dir.join(DATABASE_PATH))
+}
+
+/// Queries all bookmarks from an open buku database.
+///
+/// # Returns
+/// - `Ok(Vec<Bookmark>)` with all bookmarks, in the order they were added.
+/// - `Err(anyhow::Error)` if the database cannot be queried.
+fn query_bookmarks(conn: &Connection) -> Result<Vec<Bookmark>> {
+    let mut statement =
+        conn.prepare("SELECT URL, metadata, tags, desc FROM bookmarks ORDER BY id")?;
+
+    let rows = statement.query_map([], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, Option<String>>(1)?,
+            row.get::<_, Option<String>>(2)?,
+            row.get::<_, Option<String>>(3)?,
+        ))
+    })?;
+
+    let mut bookmarks: Vec<Bookmark> = Vec::new();
+    for row in rows {
+        let (url, title, tags, description) = row?;
+        let title = title.filter(|t| !t.trim().is_empty());
+        let mut bookmark = Bookmark::new(title.as_deref().unwrap_or(&url), &url);
+        bookmark.tags = tags
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .map(str::to_string)
+            .collect();
+        bookmark.description = description.filter(|d| !d.trim().is_empty());
+        bookmarks.push(bookmark);
+    }
+    Ok(bookmarks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_query_bookmarks() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "
+            CREATE TABLE bookmarks (
+                id integer PRIMARY KEY,
+                URL text NOT NULL UNIQUE,
+                metadata text default '',
+                tags text default ',',
+                desc text default '',
+                flags integer default 0
+            );
+            INSERT INTO bookmarks (URL, metadata, tags, desc)
+                VALUES ('https://www.rust-lang.org', 'Rust', ',lang,rust,', 'The Rust language');
+            INSERT INTO bookmarks (URL, metadata, tags, desc)
+                VALUES ('https://docs.rs', '', ',', '');
+            ",
+        )
+        .unwrap();
+
+        let bookmarks = query_bookmarks(&conn).unwrap();
+        assert_eq!(bookmarks.len(), 2);
+        assert_eq!(bookmarks[0].name, "Rust");
+        assert_eq!(bookmarks[0].tags, vec!["lang", "rust"]);
+        assert_eq!(
+            bookmarks[0].description.as_deref(),
+            Some("The Rust language")
+        );
+        assert_eq!(bookmarks[1].name, "https://docs.rs");
+        assert!(bookmarks[1].tags.is_empty());
+        assert_eq!(bookmarks[1].description, None);
+    }
+}
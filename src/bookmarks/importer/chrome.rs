@@ -1,74 +1,409 @@
-//! Chrome Bookmarks Importer
+//! Chromium-family Bookmarks Importer
 //!
-//! This module provides functionality to import bookmarks from Google Chrome profiles.
-//! It locates the Chrome bookmarks file, parses its JSON structure, and extracts bookmark data
-//! into a flat list of `Bookmark` structs.
+//! This module provides functionality to import bookmarks from Google Chrome and the other
+//! Chromium-based browsers (Chromium, Brave, Microsoft Edge, Vivaldi and Opera). They all store
+//! bookmarks in the same `Bookmarks` JSON file inside their user data directory, so only the
+//! location differs between them. The known locations are kept in [`CHROMIUM_BROWSERS`].
+//! Browsing history is read from a snapshot of the profile's `History` SQLite database, and
+//! search engines with a keyword from a snapshot of its `Web Data` database.
 //!
 //! # Supported Platforms
-//! - Linux: Locates the default Chrome bookmarks under `~/.config/google-chrome/Default/Bookmarks`.
-//! - Windows: Locates the default Chrome bookmarks under `AppData/Local/Google/Chrome/User Data/Default/Bookmarks`,
-//!   or falls back to `Profile 1` if the default does not exist.
+//! - Linux: Native installs under `~/.config/`, Flatpak installs under `~/.var/app/` and Snap
+//!   installs under `~/snap/`.
+//! - Windows: Installs under `AppData/Local/` (or `AppData/Roaming/` for Opera).
+//!
+//! The profiles inside a user data directory are discovered from its `Local State` file
+//! (`profile.info_cache`), which also provides their display names. By default the `Default`
+//! profile is imported. When `Local State` is missing, `Default`, `Profile 1` and the user data
+//! directory itself (for browsers without profile directories, like Opera) are tried instead.
 //!
 //! # Errors
 //! Returns an error if the home directory cannot be determined, the bookmarks file cannot be found,
 //! or if there are issues reading or parsing the file.
 
-use std::{fs, path::PathBuf};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    path::{Path, PathBuf},
+};
 
-use super::Bookmark;
+use super::{
+    profile::{self, Profile},
+    snapshot::Snapshot,
+    Bookmark, Browser, HistoryEntry, Import, Origin, SearchEngine,
+};
 
 use anyhow::{Context, Result};
-use serde::{Deserialize, Serialize};
+use serde::Deserialize;
+
+/// Known user data directories of a Chromium-family browser, relative to the home directory.
+struct ChromiumBrowser {
+    browser: Browser,
+    linux: &'static [&'static str],
+    windows: &'static [&'static str],
+}
+
+/// Registry of supported Chromium-family browsers and where they keep their user data.
+///
+/// Candidates are tried in order, so native installs take precedence over Flatpak and Snap ones.
+const CHROMIUM_BROWSERS: &[ChromiumBrowser] = &[
+    ChromiumBrowser {
+        browser: Browser::Chrome,
+        linux: &[
+            ".config/google-chrome",
+            ".var/app/com.google.Chrome/config/google-chrome",
+        ],
+        windows: &["AppData/Local/Google/Chrome/User Data"],
+    },
+    ChromiumBrowser {
+        browser: Browser::Chromium,
+        linux: &[
+            ".config/chromium",
+            ".var/app/org.chromium.Chromium/config/chromium",
+            "snap/chromium/common/chromium",
+        ],
+        windows: &["AppData/Local/Chromium/User Data"],
+    },
+    ChromiumBrowser {
+        browser: Browser::Brave,
+        linux: &[
+            ".config/BraveSoftware/Brave-Browser",
+            ".var/app/com.brave.Browser/config/BraveSoftware/Brave-Browser",
+            "snap/brave/current/.config/BraveSoftware/Brave-Browser",
+        ],
+        windows: &["AppData/Local/BraveSoftware/Brave-Browser/User Data"],
+    },
+    ChromiumBrowser {
+        browser: Browser::Edge,
+        linux: &[
+            ".config/microsoft-edge",
+            ".var/app/com.microsoft.Edge/config/microsoft-edge",
+        ],
+        windows: &["AppData/Local/Microsoft/Edge/User Data"],
+    },
+    ChromiumBrowser {
+        browser: Browser::Vivaldi,
+        linux: &[
+            ".config/vivaldi",
+            ".var/app/com.vivaldi.Vivaldi/config/vivaldi",
+            "snap/vivaldi/current/.config/vivaldi",
+        ],
+        windows: &["AppData/Local/Vivaldi/User Data"],
+    },
+    ChromiumBrowser {
+        browser: Browser::Opera,
+        linux: &[
+            ".config/opera",
+            ".var/app/com.opera.Opera/config/opera",
+            "snap/opera/current/.config/opera",
+        ],
+        windows: &["AppData/Roaming/Opera Software/Opera Stable"],
+    },
+];
+
+/// Profile directories probed when a user data directory has no usable `Local State` file, in
+/// order of preference. The empty entry stands for the user data directory itself.
+const PROFILE_DIRS: &[&str] = &["Default", "Profile 1", ""];
+
+/// Maximum number of history entries imported per profile.
+const HISTORY_LIMIT: usize = 10_000;
 
-/// Imports bookmarks from the default Chrome profile.
+/// Imports bookmarks and history from the selected profiles of the given Chromium-family browser.
+///
+/// # Arguments
+/// - `browser`: The browser to import from. Must be one of the browsers in [`CHROMIUM_BROWSERS`].
+/// - `selection`: Profile names or directories to import from, see [`profile::select`].
 ///
 /// # Returns
-/// - `Ok(Vec<Bookmark>)` on success.
+/// - `Ok(Import)` on success, each entry tagged with the profile it came from.
 /// - `Err(anyhow::Error)` if bookmarks cannot be imported.
-pub(crate) fn import() -> Result<Vec<Bookmark>> {
-    let file_path = get_bookmarks_file_path()?;
+pub(crate) fn import(browser: &Browser, selection: &[String]) -> Result<Import> {
+    let mut import = Import::default();
+
+    for profile in profile::select(browser, list_profiles(browser)?, selection)? {
+        let origin = Origin::new(*browser, Some(&profile.name));
+        let file_path = profile.path.join("Bookmarks");
+        let content = fs::read_to_string(&file_path)
+            .with_context(|| format!("Failed to read {:?}", file_path))?;
+        let bookmarks = parse_bookmarks(&content)
+            .with_context(|| format!("Failed to parse Chrome bookmarks at {:?}", file_path))?;
+
+        // The history is often missing or locked, for example in a new profile or with history
+        // turned off. It only adds visit data, so the bookmarks are imported without it then.
+        let history = read_history(&profile.path).unwrap_or_default();
+
+        // The bookmarks file has no visit counts, so they are taken from the history instead.
+        let visits: HashMap<&str, &HistoryEntry> =
+            history.iter().map(|e| (e.url.as_str(), e)).collect();
+        for mut bookmark in bookmarks {
+            if let Some(entry) = visits.get(bookmark.url.as_str()) {
+                bookmark.visit_count = entry.visit_count;
+                bookmark.last_used = bookmark.last_used.max(entry.last_visit);
+            }
+            bookmark.origins = vec![origin.clone()];
+            import.bookmarks.push(bookmark);
+        }
 
-    if !file_path.exists() {
-        return Err(anyhow::anyhow!("The path {:?} does not exist.", file_path));
+        import.history.extend(history.into_iter().map(|mut entry| {
+            entry.origins = vec![origin.clone()];
+            entry
+        }));
+
+        // Like the history, search engines are optional and skipped if `Web Data` is unavailable.
+        let engines = read_search_engines(&profile.path).unwrap_or_default();
+        import
+            .search_engines
+            .extend(engines.into_iter().map(|mut engine| {
+                engine.origins = vec![origin.clone()];
+                engine
+            }));
     }
 
-    let content = fs::read_to_string(&file_path)
-        .with_context(|| format!("Failed to read {:?}", file_path))?;
-    let chrome_bookmarks: ChromeRoot = serde_json::from_str(&content)
-        .with_context(|| format!("Failed to parse Chrome bookmarks at {:?}", file_path))?;
+    Ok(import)
+}
 
-    let bookmarks: Vec<Bookmark> = unpack_chrome_roots(&chrome_bookmarks);
-    Ok(bookmarks)
+/// Returns the bookmark files an import from the selected profiles reads, for detecting
+/// changes.
+///
+/// # Returns
+/// - `Ok(Vec<PathBuf>)` with the `Bookmarks` file of each profile.
+/// - `Err(anyhow::Error)` if the profiles cannot be found.
+pub(crate) fn source_files(browser: &Browser, selection: &[String]) -> Result<Vec<PathBuf>> {
+    Ok(
+        profile::select(browser, list_profiles(browser)?, selection)?
+            .into_iter()
+            .map(|profile| profile.path.join("Bookmarks"))
+            .collect(),
+    )
+}
+
+/// Returns the history and search engine databases an import from the selected profiles reads.
+///
+/// # Returns
+/// - `Ok(Vec<PathBuf>)` with the `History` and `Web Data` databases of each profile.
+/// - `Err(anyhow::Error)` if the profiles cannot be found.
+pub(crate) fn history_files(browser: &Browser, selection: &[String]) -> Result<Vec<PathBuf>> {
+    let mut files: Vec<PathBuf> = Vec::new();
+    for profile in profile::select(browser, list_profiles(browser)?, selection)? {
+        files.push(profile.path.join("History"));
+        files.push(profile.path.join("Web Data"));
+    }
+    Ok(files)
 }
 
-/// Determines the path to the Chrome bookmarks file for the default profile.
+/// Reads the most recently visited pages from the `History` database of a profile.
 ///
-/// On Windows, checks both the "Default" and "Profile 1" directories.
-/// On Linux, uses the default Chrome config directory.
+/// The database is locked while the browser is running, so a snapshot is read instead.
+///
+/// # Arguments
+/// - `profile_dir`: Path to the profile directory.
 ///
 /// # Returns
-/// - `Ok(PathBuf)` with the path to the bookmarks file.
-/// - `Err(anyhow::Error)` if the path cannot be determined.
-fn get_bookmarks_file_path() -> Result<PathBuf> {
-    if cfg!(target_os = "windows") {
-        let mut default_path = get_home_dir()?;
-        default_path.push("AppData/Local/Google/Chrome/User Data/Default/Bookmarks");
-
-        let p = if default_path.exists() {
-            default_path
-        } else {
-            let mut profile_path = get_home_dir()?;
-            profile_path.push("AppData/Local/Google/Chrome/User Data/Profile 1/Bookmarks");
-            profile_path
-        };
-        Ok(p)
+/// - `Ok(Vec<HistoryEntry>)` with up to [`HISTORY_LIMIT`] entries, most recent visit first.
+/// - `Err(anyhow::Error)` if the database cannot be copied or queried.
+fn read_history(profile_dir: &Path) -> Result<Vec<HistoryEntry>> {
+    let snapshot = Snapshot::create(&profile_dir.join("History"))?;
+    let conn = snapshot.open()?;
+
+    let mut statement = conn.prepare(
+        "
+        SELECT url, title, visit_count, last_visit_time
+        FROM urls
+        WHERE hidden = 0
+        ORDER BY last_visit_time DESC
+        LIMIT ?1
+    ",
+    )?;
+
+    let rows = statement.query_map([HISTORY_LIMIT], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, Option<String>>(1)?,
+            row.get::<_, Option<u32>>(2)?,
+            row.get::<_, Option<i64>>(3)?,
+        ))
+    })?;
+
+    let mut history: Vec<HistoryEntry> = Vec::new();
+    for row in rows {
+        let (url, title, visit_count, last_visit_time) = row?;
+        let title = title.filter(|t| !t.is_empty());
+        let mut entry = HistoryEntry::new(title.as_deref().unwrap_or(&url), &url);
+        entry.visit_count = visit_count.unwrap_or_default();
+        entry.last_visit = last_visit_time.and_then(webkit_to_unix);
+        history.push(entry);
+    }
+    Ok(history)
+}
+
+/// Reads the search engines with a keyword from the `Web Data` database of a profile.
+///
+/// A profile without the database has no search engines. The database is locked while the
+/// browser is running, so a snapshot is read instead.
+///
+/// # Arguments
+/// - `profile_dir`: Path to the profile directory.
+///
+/// # Returns
+/// - `Ok(Vec<SearchEngine>)` with the search engines, most used first.
+/// - `Err(anyhow::Error)` if the database cannot be copied or queried.
+fn read_search_engines(profile_dir: &Path) -> Result<Vec<SearchEngine>> {
+    let path = profile_dir.join("Web Data");
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let snapshot = Snapshot::create(&path)?;
+    let conn = snapshot.open()?;
+
+    let mut statement = conn.prepare(
+        "
+        SELECT short_name, keyword, url
+        FROM keywords
+        WHERE keyword != '' AND url LIKE '%{searchTerms}%'
+        ORDER BY usage_count DESC
+    ",
+    )?;
+
+    let rows = statement.query_map([], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, String>(2)?,
+        ))
+    })?;
+
+    let mut engines: Vec<SearchEngine> = Vec::new();
+    for row in rows {
+        let (name, keyword, url) = row?;
+        engines.push(SearchEngine::new(&name, &keyword, &convert_template(&url)));
+    }
+    Ok(engines)
+}
+
+/// Converts a Chrome search URL template to a `%s` template.
+///
+/// Chrome templates use `{searchTerms}` for the search terms, and further placeholders for
+/// parameters only Chrome itself can fill in. The base URL of Google is filled in, and the other
+/// placeholders are removed.
+fn convert_template(url: &str) -> String {
+    let url = url
+        .replace("{searchTerms}", "%s")
+        .replace("{google:baseURL}", "https://www.google.com/");
+
+    let mut converted = String::with_capacity(url.len());
+    let mut rest = url.as_str();
+    while let Some(start) = rest.find('{') {
+        converted.push_str(&rest[..start]);
+        match rest[start..].find('}') {
+            Some(end) => rest = &rest[start + end + 1..],
+            None => {
+                rest = &rest[start..];
+                break;
+            }
+        }
+    }
+    converted.push_str(rest);
+    converted
+}
+
+/// Converts a Chrome timestamp, in microseconds since 1601-01-01, to seconds since the Unix epoch.
+///
+/// # Returns
+/// - `Some(i64)` with the Unix timestamp, or `None` for the zero value Chrome uses for "never".
+fn webkit_to_unix(micros: i64) -> Option<i64> {
+    const SECONDS_FROM_1601_TO_1970: i64 = 11_644_473_600;
+    if micros <= 0 {
+        return None;
+    }
+    Some(micros / 1_000_000 - SECONDS_FROM_1601_TO_1970)
+}
+
+/// Returns the existing user data directories of the given browser.
+///
+/// # Returns
+/// - `Ok(Vec<PathBuf>)` with every candidate directory that exists, in order of preference.
+/// - `Err(anyhow::Error)` if the browser is not Chromium-based or the OS is unsupported.
+fn get_user_data_dirs(browser: &Browser) -> Result<Vec<PathBuf>> {
+    let entry = CHROMIUM_BROWSERS
+        .iter()
+        .find(|b| &b.browser == browser)
+        .ok_or_else(|| anyhow::anyhow!("{:?} is not a Chromium-based browser", browser))?;
+
+    let candidates = if cfg!(target_os = "windows") {
+        entry.windows
     } else if cfg!(target_os = "linux") {
-        let mut path = get_home_dir()?;
-        path.push(".config/google-chrome/Default/Bookmarks");
-        return Ok(path);
+        entry.linux
     } else {
         return Err(anyhow::anyhow!("Unsupported OS for importing bookmarks"));
+    };
+
+    let home_dir = get_home_dir()?;
+    Ok(candidates
+        .iter()
+        .map(|relative| home_dir.join(relative))
+        .filter(|path| path.is_dir())
+        .collect())
+}
+
+/// Lists all profiles of the given browser that have a bookmarks file.
+///
+/// Profiles are read from the `Local State` file of each user data directory. When that file is
+/// missing, unreadable or lists no profile with bookmarks, the directories in [`PROFILE_DIRS`]
+/// are probed instead.
+///
+/// # Returns
+/// - `Ok(Vec<Profile>)` with the default profile first.
+/// - `Err(anyhow::Error)` if the user data directories cannot be determined.
+pub(crate) fn list_profiles(browser: &Browser) -> Result<Vec<Profile>> {
+    let mut profiles: Vec<Profile> = Vec::new();
+
+    for user_data_dir in get_user_data_dirs(browser)? {
+        let mut found: Vec<Profile> = match read_local_state(&user_data_dir.join("Local State")) {
+            Ok(local_state) => local_state
+                .profile
+                .info_cache
+                .into_iter()
+                .map(|(dir_name, info)| Profile {
+                    path: user_data_dir.join(&dir_name),
+                    name: info.name.unwrap_or_else(|| dir_name.clone()),
+                    dir_name,
+                })
+                .filter(|p| p.path.join("Bookmarks").is_file())
+                .collect(),
+            Err(_) => Vec::new(),
+        };
+
+        if found.is_empty() {
+            found = PROFILE_DIRS
+                .iter()
+                .map(|dir_name| Profile {
+                    path: user_data_dir.join(dir_name),
+                    name: if dir_name.is_empty() {
+                        "Default"
+                    } else {
+                        dir_name
+                    }
+                    .to_string(),
+                    dir_name: dir_name.to_string(),
+                })
+                .filter(|p| p.path.join("Bookmarks").is_file())
+                .collect();
+        }
+
+        // Keep the default profile first, as that is the one used when none is configured.
+        found.sort_by_key(|p| p.dir_name != "Default");
+        profiles.extend(found);
     }
+
+    Ok(profiles)
+}
+
+/// Reads and parses a Chrome `Local State` file.
+fn read_local_state(path: &Path) -> Result<LocalState> {
+    let content = fs::read_to_string(path).with_context(|| format!("Failed to read {:?}", path))?;
+    let local_state = serde_json::from_str(&content)
+        .with_context(|| format!("Failed to parse Local State at {:?}", path))?;
+    Ok(local_state)
 }
 
 /// Returns the current user's home directory.
@@ -82,8 +417,22 @@ fn get_home_dir() -> Result<PathBuf> {
     Ok(profile_path)
 }
 
+/// Parses the content of a Chrome `Bookmarks` file. Falkon's `bookmarks.json` uses the same
+/// format.
+///
+/// # Returns
+/// - `Ok(Vec<Bookmark>)` with all bookmarks found.
+/// - `Err(anyhow::Error)` if the content is not a valid bookmarks file.
+pub(crate) fn parse_bookmarks(content: &str) -> Result<Vec<Bookmark>> {
+    let root: ChromeRoot = serde_json::from_str(content)?;
+    Ok(unpack_chrome_roots(&root))
+}
+
 /// Unpacks all bookmarks from the Chrome bookmarks root structure.
 ///
+/// Every root is imported, not just the bookmark bar, other and mobile bookmarks, because some
+/// Chromium builds add roots of their own. Roots that are not bookmark folders are ignored.
+///
 /// # Arguments
 /// - `root`: Reference to the deserialized Chrome bookmarks root.
 ///
@@ -91,9 +440,15 @@ fn get_home_dir() -> Result<PathBuf> {
 /// - `Vec<Bookmark>` containing all bookmarks found.
 fn unpack_chrome_roots(root: &ChromeRoot) -> Vec<Bookmark> {
     let mut bookmarks: Vec<Bookmark> = Vec::new();
-    bookmarks.extend(unpack_chrome_bookmarks(&root.roots.bookmark_bar));
-    bookmarks.extend(unpack_chrome_bookmarks(&root.roots.other));
-    bookmarks.extend(unpack_chrome_bookmarks(&root.roots.synced));
+    for (key, value) in &root.roots {
+        let Ok(mut item) = ChromeBookmarkItem::deserialize(value) else {
+            continue;
+        };
+        if item.name.is_empty() {
+            item.name = key.clone();
+        }
+        bookmarks.extend(unpack_chrome_bookmarks(&item, ""));
+    }
     bookmarks
 }
 
@@ -101,18 +456,34 @@ fn unpack_chrome_roots(root: &ChromeRoot) -> Vec<Bookmark> {
 ///
 /// # Arguments
 /// - `bookmark_item`: Reference to a Chrome bookmark item node.
+/// - `folder`: Folder path of the node's parent, empty for root nodes.
 ///
 /// # Returns
 /// - `Vec<Bookmark>` containing all bookmarks found under this node.
-fn unpack_chrome_bookmarks(bookmark_item: &ChromeBookmarkItem) -> Vec<Bookmark> {
+fn unpack_chrome_bookmarks(bookmark_item: &ChromeBookmarkItem, folder: &str) -> Vec<Bookmark> {
     let mut bookmarks: Vec<Bookmark> = Vec::new();
 
     match &bookmark_item.url {
-        Some(url) => bookmarks.push(Bookmark::new(&bookmark_item.name, url)),
+        Some(url) => {
+            let mut bookmark = Bookmark::new(&bookmark_item.name, url);
+            bookmark.folder = folder.to_string();
+            bookmark.date_added = parse_webkit_time(bookmark_item.date_added.as_deref());
+            bookmark.last_used = parse_webkit_time(bookmark_item.date_last_used.as_deref());
+            bookmark.guid = bookmark_item.guid.clone();
+            bookmark.keyword = bookmark_item.keyword.clone().filter(|k| !k.is_empty());
+            bookmark.description = bookmark_item.description.clone().filter(|d| !d.is_empty());
+            bookmark.visit_count = bookmark_item.visit_count.unwrap_or_default();
+            bookmarks.push(bookmark);
+        }
         None => {
             if let Some(children) = &bookmark_item.children {
+                let folder = if folder.is_empty() {
+                    bookmark_item.name.clone()
+                } else {
+                    format!("{}/{}", folder, bookmark_item.name)
+                };
                 for child in children {
-                    bookmarks.extend(unpack_chrome_bookmarks(child));
+                    bookmarks.extend(unpack_chrome_bookmarks(child, &folder));
                 }
             }
         }
@@ -121,22 +492,47 @@ fn unpack_chrome_bookmarks(bookmark_item: &ChromeBookmarkItem) -> Vec<Bookmark>
     bookmarks
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-struct ChromeRoot {
-    roots: ChromeBookmarks,
+/// Parses a Chrome timestamp string, as stored in the `Bookmarks` file, into a Unix timestamp.
+fn parse_webkit_time(time: Option<&str>) -> Option<i64> {
+    time.and_then(|t| t.parse().ok()).and_then(webkit_to_unix)
+}
+
+#[derive(Deserialize, Debug)]
+struct LocalState {
+    profile: LocalStateProfile,
+}
+
+#[derive(Deserialize, Debug)]
+struct LocalStateProfile {
+    info_cache: BTreeMap<String, LocalStateProfileInfo>,
+}
+
+#[derive(Deserialize, Debug)]
+struct LocalStateProfileInfo {
+    name: Option<String>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-struct ChromeBookmarks {
-    bookmark_bar: ChromeBookmarkItem,
-    other: ChromeBookmarkItem,
-    synced: ChromeBookmarkItem,
+#[derive(Deserialize, Debug)]
+struct ChromeRoot {
+    /// Root folders by key, like `bookmark_bar`, `other` and `synced`. Values are kept as raw
+    /// JSON so that unexpected entries do not fail the whole file.
+    roots: BTreeMap<String, serde_json::Value>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Deserialize, Debug)]
 struct ChromeBookmarkItem {
+    #[serde(default)]
     name: String,
     url: Option<String>,
+    guid: Option<String>,
+    /// Time the bookmark was added, as a string of microseconds since 1601-01-01.
+    date_added: Option<String>,
+    /// Time the bookmark was last opened, as a string of microseconds since 1601-01-01.
+    date_last_used: Option<String>,
+    /// Keyword, description and visit count are only written by Falkon.
+    keyword: Option<String>,
+    description: Option<String>,
+    visit_count: Option<u32>,
     children: Option<Vec<ChromeBookmarkItem>>,
 }
 
@@ -144,10 +540,103 @@ struct ChromeBookmarkItem {
 /// Tests that importing from Chrome returns at least one bookmark if the file exists.
 /// This test will pass if bookmarks are present, or do nothing if the file is missing.
 fn test_import_from_chrome() {
-    match import() {
+    match import(&Browser::Chrome, &[]) {
         Err(_) => {}
-        Ok(bookmarks) => {
-            assert!(!bookmarks.is_empty());
+        Ok(import) => {
+            assert!(!import.bookmarks.is_empty());
         }
     }
 }
+
+#[test]
+/// Tests that Chrome timestamps are converted to Unix timestamps.
+fn test_webkit_to_unix() {
+    assert_eq!(webkit_to_unix(13_343_184_000_000_000), Some(1_698_710_400));
+    assert_eq!(webkit_to_unix(0), None);
+}
+
+#[test]
+/// Tests that Chrome search URL templates are converted to `%s` templates.
+fn test_convert_template() {
+    assert_eq!(
+        convert_template("{google:baseURL}search?q={searchTerms}&{google:RLZ}ie={inputEncoding}"),
+        "https://www.google.com/search?q=%s&ie="
+    );
+    assert_eq!(
+        convert_template("https://github.com/search?q={searchTerms}"),
+        "https://github.com/search?q=%s"
+    );
+}
+
+#[test]
+/// Tests that every Chromium-family browser has an entry in the registry.
+fn test_chromium_registry_is_complete() {
+    for browser in [
+        Browser::Chrome,
+        Browser::Chromium,
+        Browser::Brave,
+        Browser::Edge,
+        Browser::Vivaldi,
+        Browser::Opera,
+    ] {
+        assert!(CHROMIUM_BROWSERS.iter().any(|b| b.browser == browser));
+    }
+}
+
+#[test]
+/// Tests that profile display names are read from a `Local State` file.
+fn test_parse_local_state() {
+    let content = r#"{
+        "profile": {
+            "info_cache": {
+                "Default": { "name": "Personal" },
+                "Profile 1": { "name": "Work" }
+            },
+            "last_used": "Profile 1"
+        }
+    }"#;
+
+    let local_state: LocalState = serde_json::from_str(content).unwrap();
+    let info_cache = local_state.profile.info_cache;
+    assert_eq!(info_cache["Default"].name.as_deref(), Some("Personal"));
+    assert_eq!(info_cache["Profile 1"].name.as_deref(), Some("Work"));
+}
+
+#[test]
+/// Tests that bookmarks files with missing or extra roots are parsed.
+fn test_unpack_chrome_roots() {
+    let content = r#"{
+        "checksum": "0",
+        "roots": {
+            "bookmark_bar": {
+                "name": "Bookmarks bar",
+                "type": "folder",
+                "children": [{
+                    "name": "Rust",
+                    "type": "url",
+                    "url": "https://www.rust-lang.org",
+                    "guid": "00000000-0000-4000-a000-000000000001",
+                    "date_added": "13343184000000000"
+                }]
+            },
+            "other": { "name": "Other bookmarks", "type": "folder", "children": [] },
+            "workspaces": {
+                "type": "folder",
+                "children": [{ "name": "Docs", "type": "url", "url": "https://docs.rs" }]
+            },
+            "sync_transaction_version": "1"
+        },
+        "version": 1
+    }"#;
+
+    let root: ChromeRoot = serde_json::from_str(content).unwrap();
+    let bookmarks = unpack_chrome_roots(&root);
+    assert_eq!(bookmarks.len(), 2);
+    assert_eq!(bookmarks[0].folder, "Bookmarks bar");
+    assert_eq!(bookmarks[0].date_added, Some(1_698_710_400));
+    assert_eq!(
+        bookmarks[0].guid.as_deref(),
+        Some("00000000-0000-4000-a000-000000000001")
+    );
+    assert_eq!(bookmarks[1].folder, "workspaces");
+}
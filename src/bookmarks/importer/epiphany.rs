@@ -0,0 +1,186 @@
+//! GNOME Web Bookmarks Importer
+//!
+//! This module provides functionality to import bookmarks from GNOME Web (Epiphany), which keeps
+//! them in a GVDB file, `bookmarks.gvdb`, in its config directory. The file has a `bookmarks`
+//! table with one entry per bookmark, keyed by URL. Each value is a tuple starting with the time
+//! the bookmark was added, its title and its ID, and ending with its tags. GNOME Web has no
+//! folders, so tags are its only way of organizing bookmarks.
+//!
+//! The layout of the tuple changed between GNOME Web versions, so its members are matched by
+//! type rather than position.
+//!
+//! # Supported Platforms
+//! - Linux: Native installs under `~/.config/` and Flatpak installs under `~/.var/app/`.
+//!
+//! # Errors
+//! Returns an error if the bookmarks file cannot be found, read or parsed.
+
+use std::{fs, path::PathBuf};
+
+use anyhow::{Context, Result};
+
+use super::{
+    gvdb::{self, Value},
+    Bookmark, Browser, Origin,
+};
+
+/// Known locations of the bookmarks file, relative to the home directory.
+const BOOKMARKS_PATHS: &[&str] = &[
+    ".config/epiphany/bookmarks.gvdb",
+    ".var/app/org.gnome.Epiphany/config/epiphany/bookmarks.gvdb",
+];
+
+/// Imports all bookmarks from GNOME Web.
+///
+/// # Returns
+/// - `Ok(Vec<Bookmark>)` on success.
+/// - `Err(anyhow::Error)` if the bookmarks file cannot be found, read or parsed.
+pub(crate) fn import() -> Result<Vec<Bookmark>> {
+    let path = bookmarks_path()?;
+    let content = fs::read(&path).with_context(|| format!("Failed to read {:?}", path))?;
+    let origin = Origin::new(Browser::GnomeWeb, None);
+
+    let bookmarks = parse(&content)
+        .with_context(|| format!("Failed to parse GNOME Web bookmarks at {:?}", path))?
+        .into_iter()
+        .map(|mut bookmark| {
+            bookmark.origins = vec![origin.clone()];
+            bookmark
+        })
+        .collect();
+    Ok(bookmarks)
+}
+
+/// Returns the path of GNOME Web's bookmarks file.
+///
+/// # Returns
+/// - `Ok(PathBuf)` with the first known location that exists.
+/// - `Err(anyhow::Error)` if the OS is unsupported or no bookmarks file exists.
+pub(crate) fn bookmarks_path() -> Result<PathBuf> {
+    if !cfg!(target_os = "linux") {
+        return Err(anyhow::anyhow!("Unsupported OS for importing bookmarks"));
+    }
+    let home_dir =
+        dirs::home_dir().ok_or_else(|| anyhow::anyhow!("Could not determine home directory."))?;
+    BOOKMARKS_PATHS
+        .iter()
+        .map(|relative| home_dir.join(relative))
+        .find(|path| path.is_file())
+        .ok_or_else(|| anyhow::anyhow!("No bookmarks found for GnomeWeb. Is it installed?"))
+}
+
+/// Parses the content of a `bookmarks.gvdb` file.
+///
+/// # Returns
+/// - `Ok(Vec<Bookmark>)` with all bookmarks found.
+/// - `Err(anyhow::Error)` if the content is not a valid GVDB file.
+fn parse(content: &[u8]) -> Result<Vec<Bookmark>> {
+    let Some(table) = gvdb::root(content)?.table("bookmarks")? else {
+        return Ok(Vec::new());
+    };
+
+    let mut bookmarks: Vec<Bookmark> = Vec::new();
+    for (url, value) in table.values()? {
+        let Value::Tuple(members) = value else {
+            continue;
+        };
+        let mut strings = members.iter().filter_map(|m| match m {
+            Value::Str(s) => Some(s),
+            _ => None,
+        });
+        let title = strings.next().filter(|t| !t.is_empty());
+        let id = strings.next();
+
+        let mut bookmark = Bookmark::new(title.unwrap_or(&url), &url);
+        bookmark.guid = id.cloned();
+        // Bookmarks are added with the time in microseconds since the Unix epoch.
+        bookmark.date_added = members.iter().find_map(|m| match m {
+            Value::Int(micros) if *micros > 0 => Some(micros / 1_000_000),
+            _ => None,
+        });
+        bookmark.tags = members
+            .iter()
+            .find_map(|m| match m {
+                Value::Array(tags) => Some(tags),
+                _ => None,
+            })
+            .map(|tags| {
+                tags.iter()
+                    .filter_map(|t| match t {
+                        Value::Str(tag) => Some(tag.clone()),
+                        _ => None,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        bookmarks.push(bookmark);
+    }
+    Ok(bookmarks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Appends a hash table with the given items to `file` and returns its start and end.
+    /// Items are `(key, kind, value start, value end)`, and their keys are appended as well.
+    fn push_table(file: &mut Vec<u8>, items: &[(&str, u8, u32, u32)]) -> (u32, u32) {
+        let mut keys: Vec<(u32, u16)> = Vec::new();
+        for (key, ..) in items {
+            keys.push((file.len() as u32, key.len() as u16));
+            file.extend(key.as_bytes());
+        }
+        while !file.len().is_multiple_of(4) {
+            file.push(0);
+        }
+
+        let start = file.len() as u32;
+        // No bloom filter words and a single bucket.
+        file.extend(0u32.to_le_bytes());
+        file.extend(1u32.to_le_bytes());
+        file.extend(0u32.to_le_bytes());
+        for ((_, kind, value_start, value_end), (key_start, key_size)) in items.iter().zip(keys) {
+            file.extend(0u32.to_le_bytes());
+            file.extend(u32::MAX.to_le_bytes());
+            file.extend(key_start.to_le_bytes());
+            file.extend(key_size.to_le_bytes());
+            file.extend([*kind, 0]);
+            file.extend(value_start.to_le_bytes());
+            file.extend(value_end.to_le_bytes());
+        }
+        (start, file.len() as u32)
+    }
+
+    #[test]
+    fn test_parse_gvdb_bookmarks() {
+        let mut file: Vec<u8> = Vec::new();
+        file.extend(b"GVariant");
+        file.extend([0; 16]);
+
+        // (xssas) holding (1700000000000000, "Rust", "id-1", ["lang"]) as a variant.
+        let value_start = file.len() as u32;
+        file.extend(1_700_000_000_000_000i64.to_le_bytes());
+        file.extend(b"Rust\0id-1\0");
+        file.extend(b"lang\0");
+        file.push(5);
+        file.extend([18, 13]);
+        file.extend(b"\0(xssas)");
+        let value_end = file.len() as u32;
+
+        let bookmarks = push_table(
+            &mut file,
+            &[("https://www.rust-lang.org", b'v', value_start, value_end)],
+        );
+        let root = push_table(&mut file, &[("bookmarks", b'H', bookmarks.0, bookmarks.1)]);
+        file[16..20].copy_from_slice(&root.0.to_le_bytes());
+        file[20..24].copy_from_slice(&root.1.to_le_bytes());
+
+        let bookmarks = parse(&file).unwrap();
+        assert_eq!(bookmarks.len(), 1);
+        assert_eq!(bookmarks[0].name, "Rust");
+        assert_eq!(bookmarks[0].url, "https://www.rust-lang.org");
+        assert_eq!(bookmarks[0].guid.as_deref(), Some("id-1"));
+        assert_eq!(bookmarks[0].date_added, Some(1_700_000_000));
+        assert_eq!(bookmarks[0].tags, vec!["lang"]);
+    }
+}
@@ -0,0 +1,126 @@
+//! Falkon Bookmarks Importer
+//!
+//! This module provides functionality to import bookmarks from Falkon. Every Falkon profile keeps
+//! its bookmarks in a `bookmarks.json` file, which uses the same format as Chrome's `Bookmarks`
+//! file and is parsed by [`chrome::parse_bookmarks`]. Falkon also stores a keyword, description
+//! and visit count with every bookmark.
+//!
+//! # Supported Platforms
+//! - Linux: Native installs under `~/.config/` and Flatpak installs under `~/.var/app/`.
+//! - Windows: Installs under `AppData/Roaming/`.
+//!
+//! The profiles are the directories inside `profiles/` that contain a bookmarks file. The
+//! profile Falkon starts with, as set in `profiles/profiles.ini`, is the default.
+//!
+//! # Errors
+//! Returns an error if no profile can be found, or if a bookmarks file cannot be read or parsed.
+
+use std::{fs, path::PathBuf};
+
+use anyhow::{Context, Result};
+use ini::Ini;
+
+use super::{
+    chrome,
+    profile::{self, Profile},
+    Bookmark, Browser, Origin,
+};
+
+/// Known profile directories of Falkon on Linux, relative to the home directory.
+const LINUX_PROFILES_DIRS: &[&str] = &[
+    ".config/falkon/profiles",
+    ".var/app/org.kde.falkon/config/falkon/profiles",
+];
+
+/// Known profile directories of Falkon on Windows, relative to the home directory.
+const WINDOWS_PROFILES_DIRS: &[&str] = &["AppData/Roaming/falkon/profiles"];
+
+/// Name of the profile Falkon creates on first start.
+const DEFAULT_PROFILE: &str = "default";
+
+/// Imports bookmarks from the selected Falkon profiles.
+///
+/// # Arguments
+/// - `selection`: Profile names to import from, see [`profile::select`].
+///
+/// # Returns
+/// - `Ok(Vec<Bookmark>)` on success, each bookmark tagged with the profile it came from.
+/// - `Err(anyhow::Error)` if a profile cannot be found, or its bookmarks cannot be read.
+pub(crate) fn import(selection: &[String]) -> Result<Vec<Bookmark>> {
+    let mut bookmarks: Vec<Bookmark> = Vec::new();
+    for profile in profile::select(&Browser::Falkon, list_profiles()?, selection)? {
+        let origin = Origin::new(Browser::Falkon, Some(&profile.name));
+        let file_path = profile.path.join("bookmarks.json");
+        let content = fs::read_to_string(&file_path)
+            .with_context(|| format!("Failed to read {:?}", file_path))?;
+        let parsed = chrome::parse_bookmarks(&content)
+            .with_context(|| format!("Failed to parse Falkon bookmarks at {:?}", file_path))?;
+
+        bookmarks.extend(parsed.into_iter().map(|mut bookmark| {
+            bookmark.origins = vec![origin.clone()];
+            bookmark
+        }));
+    }
+    Ok(bookmarks)
+}
+
+/// Returns the files an import from the selected profiles reads, for detecting changes.
+///
+/// # Returns
+/// - `Ok(Vec<PathBuf>)` with the bookmarks file of each profile.
+/// - `Err(anyhow::Error)` if the profiles cannot be found.
+pub(crate) fn source_files(selection: &[String]) -> Result<Vec<PathBuf>> {
+    Ok(
+        profile::select(&Browser::Falkon, list_profiles()?, selection)?
+            .into_iter()
+            .map(|profile| profile.path.join("bookmarks.json"))
+            .collect(),
+    )
+}
+
+/// Lists all Falkon profiles with a bookmarks file, with the start profile first.
+pub(crate) fn list_profiles() -> Result<Vec<Profile>> {
+    let candidates = if cfg!(target_os = "windows") {
+        WINDOWS_PROFILES_DIRS
+    } else if cfg!(target_os = "linux") {
+        LINUX_PROFILES_DIRS
+    } else {
+        return Err(anyhow::anyhow!("Unsupported OS for importing bookmarks"));
+    };
+    let home_dir =
+        dirs::home_dir().ok_or_else(|| anyhow::anyhow!("Could not determine home directory."))?;
+
+    let mut profiles: Vec<Profile> = Vec::new();
+    for profiles_dir in candidates.iter().map(|relative| home_dir.join(relative)) {
+        let Ok(entries) = fs::read_dir(&profiles_dir) else {
+            continue;
+        };
+        let start_profile = Ini::load_from_file(profiles_dir.join("profiles.ini"))
+            .ok()
+            .and_then(|ini| {
+                ini.get_from(Some("Profiles"), "startProfile")
+                    .map(|p| p.trim_matches('"').to_string())
+            })
+            .unwrap_or_else(|| DEFAULT_PROFILE.to_string());
+
+        let mut found: Vec<Profile> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.join("bookmarks.json").is_file())
+            .filter_map(|path| {
+                let dir_name = path.file_name()?.to_string_lossy().into_owned();
+                Some(Profile {
+                    name: dir_name.clone(),
+                    dir_name,
+                    path,
+                })
+            })
+            .collect();
+        found.sort_by(|a, b| a.dir_name.cmp(&b.dir_name));
+        // Keep the start profile first, as that is the one used when none is configured.
+        found.sort_by_key(|p| p.dir_name != start_profile);
+        profiles.extend(found);
+    }
+
+    Ok(profiles)
+}
@@ -1,99 +1,311 @@
-//! Firefox Bookmarks Importer
+//! Gecko-family Bookmarks Importer
 //!
-//! This module provides functionality to import bookmarks from Mozilla Firefox profiles.
-//! It locates the Firefox profile directory, finds the `places.sqlite` database,
-//! copies it to a temporary location (to avoid file locks), and extracts bookmark data
-//! using an SQLite query.
+//! This module provides functionality to import bookmarks from Mozilla Firefox and the browsers
+//! built on it (LibreWolf, Waterfox, Floorp and Zen), which all share the same profile layout and
+//! `places.sqlite` schema. The known profile locations are kept in [`GECKO_BROWSERS`].
+//!
+//! It resolves the profiles listed in `profiles.ini`, finds the `places.sqlite` database of each
+//! selected profile, takes a snapshot of it including uncheckpointed WAL data (to avoid file
+//! locks), and extracts bookmark and history data from the snapshot using SQLite queries.
+//!
+//! # Profile Resolution
+//! Profiles are read from `profiles.ini` in the Firefox data directory. Both relative
+//! (`IsRelative=1`) and absolute profile paths are supported. The default profile is the one
+//! Firefox itself would pick: the install default from `installs.ini` or the `[Install...]`
+//! sections of `profiles.ini` (locked ones first), then the profile marked `Default=1`, then the
+//! first listed profile. Profiles without a `places.sqlite` database, like deleted profiles or
+//! ones on an unmounted drive that `profiles.ini` still lists, are left out.
 //!
 //! # Supported Platforms
-//! - Linux: Locates the default Firefox profile under `~/.mozilla/firefox/`.
-//! - Windows: Not yet implemented (see `todo!`).
+//! - Linux: Native installs (e.g. `~/.mozilla/firefox/`), Flatpak installs under `~/.var/app/`
+//!   and Snap installs under `~/snap/`. If several of them exist, for example after switching
+//!   from the native package to the Snap, the one used most recently is imported.
+//! - Windows: Installs under `AppData/Roaming/`.
 //!
 //! # Errors
 //! Returns an error if the home directory cannot be determined, the profile or database
 //! file cannot be found, or if there are issues reading or querying the database.
 
-use crate::bookmarks::Bookmark;
+use super::{
+    profile::{self, Profile},
+    snapshot::Snapshot,
+    Bookmark, Browser, HistoryEntry, Import, Origin,
+};
 
-use anyhow::Result;
+use anyhow::{Context, Result};
+use ini::Ini;
 use rusqlite::Connection;
-use std::{fs, path::PathBuf};
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
 
-/// Imports bookmarks from the default Firefox profile.
+/// Known data directories of a Gecko-based browser, relative to the home directory.
+struct GeckoBrowser {
+    browser: Browser,
+    linux: &'static [&'static str],
+    windows: &'static [&'static str],
+}
+
+/// Registry of supported Gecko-based browsers and where they keep `profiles.ini`.
+///
+/// Candidates are listed in order of preference, so a native install wins over Flatpak and Snap
+/// ones that were last used at the same time.
+const GECKO_BROWSERS: &[GeckoBrowser] = &[
+    GeckoBrowser {
+        browser: Browser::Firefox,
+        linux: &[
+            ".mozilla/firefox",
+            ".var/app/org.mozilla.firefox/.mozilla/firefox",
+            "snap/firefox/common/.mozilla/firefox",
+        ],
+        windows: &["AppData/Roaming/Mozilla/Firefox"],
+    },
+    GeckoBrowser {
+        browser: Browser::LibreWolf,
+        linux: &[
+            ".librewolf",
+            ".var/app/io.gitlab.librewolf-community/.librewolf",
+        ],
+        windows: &["AppData/Roaming/librewolf"],
+    },
+    GeckoBrowser {
+        browser: Browser::Waterfox,
+        linux: &[".waterfox", ".var/app/net.waterfox.waterfox/.waterfox"],
+        windows: &["AppData/Roaming/Waterfox"],
+    },
+    GeckoBrowser {
+        browser: Browser::Floorp,
+        linux: &[".floorp", ".var/app/one.ablaze.floorp/.floorp"],
+        windows: &["AppData/Roaming/Floorp"],
+    },
+    GeckoBrowser {
+        browser: Browser::Zen,
+        linux: &[".zen", ".var/app/app.zen_browser.zen/.zen"],
+        windows: &["AppData/Roaming/zen"],
+    },
+];
+
+/// Imports bookmarks and history from the selected profiles of the given Gecko-based browser.
+///
+/// # Arguments
+/// - `browser`: The browser to import from. Must be one of the browsers in [`GECKO_BROWSERS`].
+/// - `selection`: Profile names or directories to import from, see [`profile::select`].
 ///
 /// # Returns
-/// - `Ok(Vec<Bookmark>)` on success.
+/// - `Ok(Import)` on success, each entry tagged with the profile it came from.
 /// - `Err(anyhow::Error)` if bookmarks cannot be imported.
-pub(crate) fn import() -> Result<Vec<Bookmark>> {
-    let file_path = get_places_file_path()?;
-    let bookmarks = read_bookmarks(&file_path)?;
-    Ok(bookmarks)
+pub(crate) fn import(browser: &Browser, selection: &[String]) -> Result<Import> {
+    let mut import = Import::default();
+    for profile in profile::select(browser, list_profiles(browser)?, selection)? {
+        let origin = Origin::new(*browser, Some(&profile.name));
+        let snapshot = Snapshot::create(&profile.path.join("places.sqlite"))?;
+        let conn = snapshot.open()?;
+
+        let (bookmarks, skipped) = query_bookmarks(&conn)?;
+        import.skipped += skipped;
+        import
+            .bookmarks
+            .extend(bookmarks.into_iter().map(|mut bookmark| {
+                bookmark.origins = vec![origin.clone()];
+                bookmark
+            }));
+
+        match query_history(&conn) {
+            Ok(history) => import.history.extend(history.into_iter().map(|mut entry| {
+                entry.origins = vec![origin.clone()];
+                entry
+            })),
+            Err(e) => import
+                .errors
+                .push(format!("Failed to import history from {}: {}", origin, e)),
+        }
+    }
+    Ok(import)
 }
 
-/// Determines the path to the `places.sqlite` file for the default Firefox profile.
+/// Returns the files an import from the selected profiles reads, for detecting changes.
 ///
-/// Searches for a profile directory ending with `.default-release`.
+/// # Returns
+/// - `Ok(Vec<PathBuf>)` with the `places.sqlite` database of each profile and its write-ahead
+///   log, which holds recent changes while the browser is running.
+/// - `Err(anyhow::Error)` if the profiles cannot be found.
+pub(crate) fn source_files(browser: &Browser, selection: &[String]) -> Result<Vec<PathBuf>> {
+    let mut files: Vec<PathBuf> = Vec::new();
+    for profile in profile::select(browser, list_profiles(browser)?, selection)? {
+        files.push(profile.path.join("places.sqlite"));
+        files.push(profile.path.join("places.sqlite-wal"));
+    }
+    Ok(files)
+}
+
+/// Lists the profiles of the given browser, from its data directory that was used most
+/// recently.
+///
+/// Leftover data directories of other installs of the browser are ignored, see
+/// [`most_recent_root`].
+pub(crate) fn list_profiles(browser: &Browser) -> Result<Vec<Profile>> {
+    let mut roots: Vec<Vec<Profile>> = Vec::new();
+    for root in get_profiles_roots(browser)? {
+        roots.push(read_profiles(&root)?);
+    }
+    Ok(most_recent_root(roots))
+}
+
+/// Picks the profiles of the data directory whose `places.sqlite` was modified most recently,
+/// which is the install in use. Ties go to the directory listed first, in the order of
+/// preference of [`GECKO_BROWSERS`].
+///
+/// # Arguments
+/// - `roots`: The usable profiles of each data directory, in order of preference.
+fn most_recent_root(roots: Vec<Vec<Profile>>) -> Vec<Profile> {
+    let last_used = |profiles: &Vec<Profile>| {
+        profiles
+            .iter()
+            .filter_map(|p| fs::metadata(p.path.join("places.sqlite")).ok())
+            .filter_map(|m| m.modified().ok())
+            .max()
+    };
+
+    let mut best: Option<(Vec<Profile>, Option<SystemTime>)> = None;
+    for profiles in roots.into_iter().filter(|p| !p.is_empty()) {
+        let modified = last_used(&profiles);
+        if best.as_ref().is_none_or(|(_, best)| modified > *best) {
+            best = Some((profiles, modified));
+        }
+    }
+    best.map(|(profiles, _)| profiles).unwrap_or_default()
+}
+
+/// Returns the existing data directories of the given browser that contain `profiles.ini`.
 ///
 /// # Returns
-/// - `Ok(PathBuf)` with the path to a temporary copy of `places.sqlite`.
-/// - `Err(anyhow::Error)` if the profile or database cannot be found.
-fn get_places_file_path() -> Result<PathBuf> {
-    if cfg!(target_os = "windows") {
-        let mut base_path = get_home_dir()?;
-        base_path.push("AppData/Roaming/Mozilla/Firefox/Profiles/");
-        Ok(places_file_path(base_path)?)
+/// - `Ok(Vec<PathBuf>)` with every candidate directory that exists, in order of preference.
+/// - `Err(anyhow::Error)` if the browser is not Gecko-based or the OS is unsupported.
+fn get_profiles_roots(browser: &Browser) -> Result<Vec<PathBuf>> {
+    let entry = GECKO_BROWSERS
+        .iter()
+        .find(|b| &b.browser == browser)
+        .ok_or_else(|| anyhow::anyhow!("{:?} is not a Gecko-based browser", browser))?;
+
+    let candidates = if cfg!(target_os = "windows") {
+        entry.windows
     } else if cfg!(target_os = "linux") {
-        let mut base_path = get_home_dir()?;
-        base_path.push(".mozilla/firefox/");
-        Ok(places_file_path(base_path)?)
+        entry.linux
     } else {
         return Err(anyhow::anyhow!("Unsupported OS for importing bookmarks"));
-    }
+    };
+
+    let home_dir = get_home_dir()?;
+    Ok(candidates
+        .iter()
+        .map(|relative| home_dir.join(relative))
+        .filter(|path| path.join("profiles.ini").is_file())
+        .collect())
 }
 
-/// Locates the Firefox profile directory and copies `places.sqlite` to a temporary file.
+/// Reads the profiles from `profiles.ini` in the given data directory that have a
+/// `places.sqlite` database.
 ///
 /// # Arguments
-/// - `local_base_path`: Path to the Firefox profiles directory.
+/// - `root`: Path to the Firefox data directory.
 ///
 /// # Returns
-/// - `Ok(PathBuf)` with the path to the temporary copy of `places.sqlite`.
-/// - `Err(anyhow::Error)` if the profile or database cannot be found.
-fn places_file_path(local_base_path: PathBuf) -> Result<PathBuf> {
-    // Locate Firefox profile directory.
-    let profile_dir = match fs::read_dir(local_base_path)?
-        .filter_map(Result::ok)
-        .find(|e| {
-            e.path().is_dir()
-                && e.path().file_name().is_some_and(|os_str| {
-                    os_str
-                        .to_str()
-                        .is_some_and(|name| name.ends_with("default-release"))
-                })
-        }) {
-        None => return Err(anyhow::anyhow!("Firefox profile directory not found.")),
-        Some(entry) => entry,
-    };
+/// - `Ok(Vec<Profile>)` with the usable profiles, the default profile first.
+/// - `Err(anyhow::Error)` if `profiles.ini` cannot be read.
+fn read_profiles(root: &Path) -> Result<Vec<Profile>> {
+    let profiles_path = root.join("profiles.ini");
+    let profiles_ini = Ini::load_from_file(&profiles_path)
+        .with_context(|| format!("Failed to read {:?}", profiles_path))?;
+    // installs.ini only exists for newer Firefox versions, so it is optional.
+    let installs_ini = Ini::load_from_file(root.join("installs.ini")).ok();
 
-    // Locate file 'places.sqlite' in profile directory.
-    let places_entry = match fs::read_dir(profile_dir.path())?
-        .filter_map(Result::ok)
-        .find(|e| {
-            e.path()
-                .file_name()
-                .is_some_and(|name| name == "places.sqlite")
-        }) {
-        None => return Err(anyhow::anyhow!("File 'places.sqlite' was not found.")),
-        Some(entry) => entry,
-    };
+    let mut profiles = parse_profiles(root, &profiles_ini, installs_ini.as_ref());
+    profiles.retain(|p| p.path.join("places.sqlite").is_file());
+    Ok(profiles)
+}
 
-    let places_path = places_entry.path();
+/// Extracts the profiles from parsed `profiles.ini` and `installs.ini` files.
+///
+/// # Arguments
+/// - `root`: Path to the Firefox data directory that relative profile paths are resolved against.
+/// - `profiles_ini`: The parsed `profiles.ini`.
+/// - `installs_ini`: The parsed `installs.ini`, if present.
+///
+/// # Returns
+/// - `Vec<Profile>` with all profiles, the default profile first.
+fn parse_profiles(root: &Path, profiles_ini: &Ini, installs_ini: Option<&Ini>) -> Vec<Profile> {
+    let mut profiles: Vec<(Profile, &str, bool)> = Vec::new();
+    for (section, properties) in profiles_ini.iter() {
+        if !section.is_some_and(|s| s.starts_with("Profile")) {
+            continue;
+        }
+
+        let Some(raw_path) = properties.get("Path") else {
+            continue;
+        };
 
-    // The file is locked while Firefox is running so we make a temp copy to read from.
-    let temp_file_path = std::env::temp_dir().join("places_copy.sqlite");
-    std::fs::copy(&places_path, &temp_file_path)?;
+        let path = if properties.get("IsRelative") == Some("0") {
+            PathBuf::from(raw_path)
+        } else {
+            root.join(raw_path)
+        };
 
-    Ok(temp_file_path)
+        let dir_name = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or(raw_path)
+            .to_string();
+
+        let profile = Profile {
+            name: properties.get("Name").unwrap_or(&dir_name).to_string(),
+            dir_name,
+            path,
+        };
+        profiles.push((profile, raw_path, properties.get("Default") == Some("1")));
+    }
+
+    let default_path = install_default(profiles_ini, installs_ini);
+    profiles.sort_by_key(|(_, raw_path, is_default)| match default_path {
+        Some(default_path) if default_path == *raw_path => 0,
+        _ if *is_default => 1,
+        _ => 2,
+    });
+
+    profiles
+        .into_iter()
+        .map(|(profile, _, _)| profile)
+        .collect()
+}
+
+/// Returns the raw `Path` of the profile an installation of Firefox uses by default.
+///
+/// Install defaults are listed in `installs.ini` and in the `[Install...]` sections of
+/// `profiles.ini`. Locked defaults take precedence over unlocked ones.
+fn install_default<'a>(profiles_ini: &'a Ini, installs_ini: Option<&'a Ini>) -> Option<&'a str> {
+    let from_profiles = profiles_ini
+        .iter()
+        .filter(|(section, _)| section.is_some_and(|s| s.starts_with("Install")));
+    let from_installs = installs_ini
+        .into_iter()
+        .flat_map(|ini| ini.iter())
+        .filter(|(section, _)| section.is_some());
+
+    let installs: Vec<(&str, bool)> = from_profiles
+        .chain(from_installs)
+        .filter_map(|(_, properties)| {
+            let path = properties.get("Default")?;
+            Some((path, properties.get("Locked") == Some("1")))
+        })
+        .collect();
+
+    installs
+        .iter()
+        .find(|(_, locked)| *locked)
+        .or_else(|| installs.first())
+        .map(|(path, _)| *path)
 }
 
 /// Returns the current user's home directory.
@@ -107,34 +319,485 @@ fn get_home_dir() -> Result<PathBuf> {
     Ok(profile_path)
 }
 
-/// Reads bookmarks from the given `places.sqlite` database file.
+/// Display names of the built-in root folders, by their fixed GUIDs.
+const ROOT_FOLDER_NAMES: &[(&str, &str)] = &[
+    ("menu________", "Bookmarks Menu"),
+    ("toolbar_____", "Bookmarks Toolbar"),
+    ("unfiled_____", "Other Bookmarks"),
+    ("mobile______", "Mobile Bookmarks"),
+    ("tags________", "Tags"),
+];
+
+/// GUID of the folder all other folders descend from.
+const ROOT_GUID: &str = "root________";
+
+/// Maximum number of history entries imported per profile.
+const HISTORY_LIMIT: usize = 10_000;
+
+/// Queries all bookmarks from an open `places.sqlite` database.
+///
+/// Bookmarks without a title are named after their page title, or their URL when the page has
+/// no title either. `place:` URLs, which are saved searches rather than pages, are left out.
+/// Rows that cannot be read, like bookmarks without a URL, are skipped and counted, and so are
+/// unreadable tag and folder rows.
 ///
 /// # Arguments
-/// - `db_path`: Path to the SQLite database.
+/// - `conn`: Connection to the database.
 ///
 /// # Returns
-/// - `Ok(Vec<Bookmark>)` with all found bookmarks.
-/// - `Err(anyhow::Error)` if the database cannot be read or queried.
-fn read_bookmarks(db_path: &PathBuf) -> Result<Vec<Bookmark>> {
-    let conn = Connection::open(db_path)?;
+/// - `Ok((Vec<Bookmark>, usize))` with all found bookmarks, including their folder paths, and
+///   the number of skipped rows.
+/// - `Err(anyhow::Error)` if the database cannot be queried.
+fn query_bookmarks(conn: &Connection) -> Result<(Vec<Bookmark>, usize)> {
+    let (folder_paths, skipped_folders) = query_folder_paths(conn)?;
+    let (tags, skipped_tags) = query_tags(conn)?;
 
+    // Entries inside tag folders only record that a place has a tag, so they are skipped here
+    // and picked up by `query_tags` instead.
     let mut statement = conn.prepare(
         "
-        SELECT moz_bookmarks.title, moz_places.url
+        SELECT moz_bookmarks.title, moz_places.url, moz_bookmarks.parent, moz_places.id,
+            (SELECT keyword FROM moz_keywords WHERE place_id = moz_places.id LIMIT 1),
+            moz_places.frecency, moz_places.visit_count, moz_places.last_visit_date,
+            moz_places.title
         FROM moz_bookmarks
         JOIN moz_places ON moz_bookmarks.fk = moz_places.id
         WHERE moz_bookmarks.type = 1
+            AND (moz_places.url IS NULL OR moz_places.url NOT LIKE 'place:%')
+            AND moz_bookmarks.parent NOT IN (
+                SELECT tag.id
+                FROM moz_bookmarks AS tag
+                JOIN moz_bookmarks AS tags_root ON tag.parent = tags_root.id
+                WHERE tags_root.guid = 'tags________'
+            )
     ",
     )?;
 
     let rows = statement.query_map([], |row| {
-        Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        Ok((
+            row.get::<_, Option<String>>(0)?,
+            row.get::<_, Option<String>>(1)?,
+            row.get::<_, i64>(2)?,
+            row.get::<_, i64>(3)?,
+            row.get::<_, Option<String>>(4)?,
+            row.get::<_, Option<i64>>(5)?,
+            row.get::<_, Option<u32>>(6)?,
+            row.get::<_, Option<i64>>(7)?,
+            row.get::<_, Option<String>>(8)?,
+        ))
     })?;
 
     let mut bookmarks: Vec<Bookmark> = Vec::new();
+    let mut skipped: usize = skipped_folders + skipped_tags;
+    for row in rows {
+        let Ok((
+            title,
+            Some(url),
+            parent,
+            place_id,
+            keyword,
+            frecency,
+            visit_count,
+            last_visit,
+            page_title,
+        )) = row
+        else {
+            skipped += 1;
+            continue;
+        };
+
+        let name = [title, page_title]
+            .into_iter()
+            .flatten()
+            .find(|t| !t.trim().is_empty())
+            .unwrap_or_else(|| url.clone());
+        let mut bookmark = Bookmark::new(name.as_str(), url.as_str());
+        if let Some(folder) = folder_paths.get(&parent) {
+            bookmark.folder = folder.clone();
+        }
+        if let Some(tags) = tags.get(&place_id) {
+            bookmark.tags = tags.clone();
+        }
+        bookmark.keyword = keyword;
+        bookmark.frecency = frecency.unwrap_or_default();
+        bookmark.visit_count = visit_count.unwrap_or_default();
+        // Visit dates are stored in microseconds since the Unix epoch.
+        bookmark.last_used = last_visit.map(|micros| micros / 1_000_000);
+        bookmarks.push(bookmark);
+    }
+    Ok((bookmarks, skipped))
+}
+
+/// Queries the most recently visited pages.
+///
+/// # Arguments
+/// - `conn`: Connection to the database.
+///
+/// # Returns
+/// - `Ok(Vec<HistoryEntry>)` with up to [`HISTORY_LIMIT`] entries, most recent visit first.
+/// - `Err(anyhow::Error)` if the database cannot be queried.
+fn query_history(conn: &Connection) -> Result<Vec<HistoryEntry>> {
+    let mut statement = conn.prepare(
+        "
+        SELECT moz_places.url, moz_places.title, moz_places.visit_count,
+            MAX(moz_historyvisits.visit_date) AS last_visit
+        FROM moz_places
+        JOIN moz_historyvisits ON moz_historyvisits.place_id = moz_places.id
+        GROUP BY moz_places.id
+        ORDER BY last_visit DESC
+        LIMIT ?1
+    ",
+    )?;
+
+    let rows = statement.query_map([HISTORY_LIMIT], |row| {
+        Ok((
+            row.get::<_, Option<String>>(0)?,
+            row.get::<_, Option<String>>(1)?,
+            row.get::<_, Option<u32>>(2)?,
+            row.get::<_, Option<i64>>(3)?,
+        ))
+    })?;
+
+    let mut history: Vec<HistoryEntry> = Vec::new();
     for row in rows {
-        let (title, url) = row?;
-        bookmarks.push(Bookmark::new(title.as_str(), url.as_str()));
+        // History is a convenience, so unreadable rows are dropped without being counted.
+        let Ok((Some(url), title, visit_count, last_visit)) = row else {
+            continue;
+        };
+        let title = title.filter(|t| !t.is_empty());
+        let mut entry = HistoryEntry::new(title.as_deref().unwrap_or(&url), &url);
+        entry.visit_count = visit_count.unwrap_or_default();
+        // Visit dates are stored in microseconds since the Unix epoch.
+        entry.last_visit = last_visit.map(|micros| micros / 1_000_000);
+        history.push(entry);
+    }
+    Ok(history)
+}
+
+/// Queries the tags of all places.
+///
+/// Firefox stores a tag as a folder under the tags root, containing one entry for every tagged
+/// place.
+///
+/// # Arguments
+/// - `conn`: Connection to the database.
+///
+/// # Returns
+/// - `Ok((HashMap<i64, Vec<String>>, usize))` mapping place ids to their tag names, and the
+///   number of rows that could not be read and were skipped.
+/// - `Err(anyhow::Error)` if the database cannot be queried.
+fn query_tags(conn: &Connection) -> Result<(HashMap<i64, Vec<String>>, usize)> {
+    let mut statement = conn.prepare(
+        "
+        SELECT entry.fk, tag.title
+        FROM moz_bookmarks AS entry
+        JOIN moz_bookmarks AS tag ON entry.parent = tag.id
+        JOIN moz_bookmarks AS tags_root ON tag.parent = tags_root.id
+        WHERE entry.type = 1 AND tags_root.guid = 'tags________'
+        ORDER BY tag.title
+    ",
+    )?;
+
+    let rows = statement.query_map([], |row| {
+        Ok((row.get::<_, i64>(0)?, row.get::<_, Option<String>>(1)?))
+    })?;
+
+    let mut tags: HashMap<i64, Vec<String>> = HashMap::new();
+    let mut skipped: usize = 0;
+    for row in rows {
+        match row {
+            Ok((place_id, Some(tag))) => tags.entry(place_id).or_default().push(tag),
+            Ok((_, None)) => {}
+            Err(_) => skipped += 1,
+        }
+    }
+    Ok((tags, skipped))
+}
+
+/// Queries all folders and resolves their full paths by following the parent chain.
+///
+/// # Arguments
+/// - `conn`: Connection to the database.
+///
+/// # Returns
+/// - `Ok((HashMap<i64, String>, usize))` mapping folder ids to paths like
+///   `Bookmarks Menu/Work/Infra`, and the number of rows that could not be read and were
+///   skipped.
+/// - `Err(anyhow::Error)` if the database cannot be queried.
+fn query_folder_paths(conn: &Connection) -> Result<(HashMap<i64, String>, usize)> {
+    let mut statement = conn.prepare(
+        "
+        SELECT id, parent, title, guid
+        FROM moz_bookmarks
+        WHERE type = 2
+    ",
+    )?;
+
+    let rows = statement.query_map([], |row| {
+        Ok((
+            row.get::<_, i64>(0)?,
+            row.get::<_, i64>(1)?,
+            row.get::<_, Option<String>>(2)?,
+            row.get::<_, Option<String>>(3)?,
+        ))
+    })?;
+
+    let mut folders: HashMap<i64, (i64, Option<String>)> = HashMap::new();
+    let mut skipped: usize = 0;
+    for row in rows {
+        let Ok((id, parent, title, guid)) = row else {
+            skipped += 1;
+            continue;
+        };
+        let name = match guid.as_deref() {
+            Some(ROOT_GUID) => None,
+            Some(guid) => ROOT_FOLDER_NAMES
+                .iter()
+                .find(|(root_guid, _)| *root_guid == guid)
+                .map(|(_, name)| name.to_string())
+                .or(title),
+            None => title,
+        };
+        folders.insert(id, (parent, name));
+    }
+
+    let mut paths: HashMap<i64, String> = HashMap::new();
+    for &id in folders.keys() {
+        let mut names: Vec<&str> = Vec::new();
+        let mut current = id;
+        // The step limit guards against cycles in a corrupt database.
+        for _ in 0..folders.len() {
+            let Some((parent, name)) = folders.get(&current) else {
+                break;
+            };
+            if let Some(name) = name {
+                names.push(name);
+            }
+            current = *parent;
+        }
+        names.reverse();
+        paths.insert(id, names.join("/"));
+    }
+
+    Ok((paths, skipped))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(profiles: &str, installs: Option<&str>) -> Vec<Profile> {
+        let profiles_ini = Ini::load_from_str(profiles).unwrap();
+        let installs_ini = installs.map(|s| Ini::load_from_str(s).unwrap());
+        parse_profiles(
+            Path::new("/home/user/.mozilla/firefox"),
+            &profiles_ini,
+            installs_ini.as_ref(),
+        )
+    }
+
+    #[test]
+    fn test_parse_profiles_prefers_locked_install_default() {
+        let profiles = parse(
+            "[Profile1]\nName=default\nIsRelative=1\nPath=abcd.default\nDefault=1\n\n\
+             [Profile0]\nName=default-release\nIsRelative=1\nPath=efgh.default-release\n\n\
+             [General]\nStartWithLastProfile=1\nVersion=2\n",
+            Some("[4F96D1932A9F858E]\nDefault=efgh.default-release\nLocked=1\n"),
+        );
+
+        let names: Vec<&str> = profiles.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["default-release", "default"]);
+        assert_eq!(
+            profiles[0].path,
+            PathBuf::from("/home/user/.mozilla/firefox/efgh.default-release")
+        );
+    }
+
+    #[test]
+    fn test_parse_profiles_supports_absolute_paths() {
+        let profiles = parse(
+            "[Profile0]\nName=Work\nIsRelative=0\nPath=/data/firefox/work\n\n\
+             [Profile1]\nName=Personal\nIsRelative=1\nPath=ijkl.personal\nDefault=1\n",
+            None,
+        );
+
+        assert_eq!(profiles[0].name, "Personal");
+        assert_eq!(profiles[1].path, PathBuf::from("/data/firefox/work"));
+        assert_eq!(profiles[1].dir_name, "work");
+    }
+
+    #[test]
+    fn test_read_profiles_skips_profiles_without_database() {
+        let root = tempfile::tempdir().unwrap();
+        std::fs::write(
+            root.path().join("profiles.ini"),
+            "[Profile0]\nName=Stale\nIsRelative=1\nPath=abcd.stale\nDefault=1\n\n\
+             [Profile1]\nName=Work\nIsRelative=1\nPath=efgh.work\n",
+        )
+        .unwrap();
+        std::fs::create_dir(root.path().join("abcd.stale")).unwrap();
+        std::fs::create_dir(root.path().join("efgh.work")).unwrap();
+        std::fs::write(root.path().join("efgh.work/places.sqlite"), "").unwrap();
+
+        let profiles = read_profiles(root.path()).unwrap();
+        let names: Vec<&str> = profiles.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["Work"]);
+    }
+
+    #[test]
+    fn test_most_recent_root_wins() {
+        let dir = tempfile::tempdir().unwrap();
+        let profile = |name: &str, age_secs: u64| {
+            let path = dir.path().join(name);
+            std::fs::create_dir(&path).unwrap();
+            let database = std::fs::File::create(path.join("places.sqlite")).unwrap();
+            let modified = SystemTime::now() - std::time::Duration::from_secs(age_secs);
+            database.set_modified(modified).unwrap();
+            Profile {
+                dir_name: name.to_string(),
+                name: name.to_string(),
+                path,
+            }
+        };
+
+        let native = vec![profile("native", 86_400)];
+        let snap = vec![profile("snap-old", 3_600), profile("snap-new", 60)];
+        let profiles = most_recent_root(vec![Vec::new(), native, snap]);
+        let names: Vec<&str> = profiles.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["snap-old", "snap-new"]);
+    }
+
+    /// Creates an in-memory database with the parts of the `places.sqlite` schema we query.
+    fn places_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "
+            CREATE TABLE moz_places (
+                id INTEGER PRIMARY KEY, url TEXT, title TEXT, visit_count INTEGER,
+                frecency INTEGER, last_visit_date INTEGER
+            );
+            CREATE TABLE moz_bookmarks (
+                id INTEGER PRIMARY KEY, type INTEGER, fk INTEGER, parent INTEGER,
+                title TEXT, guid TEXT
+            );
+            CREATE TABLE moz_keywords (id INTEGER PRIMARY KEY, keyword TEXT, place_id INTEGER);
+            CREATE TABLE moz_historyvisits (id INTEGER PRIMARY KEY, place_id INTEGER, visit_date INTEGER);
+            INSERT INTO moz_bookmarks VALUES (1, 2, NULL, 0, '', 'root________');
+            INSERT INTO moz_bookmarks VALUES (2, 2, NULL, 1, 'menu', 'menu________');
+            INSERT INTO moz_bookmarks VALUES (3, 2, NULL, 1, 'toolbar', 'toolbar_____');
+            INSERT INTO moz_bookmarks VALUES (4, 2, NULL, 1, 'tags', 'tags________');
+            INSERT INTO moz_bookmarks VALUES (5, 2, NULL, 1, 'unfiled', 'unfiled_____');
+            ",
+        )
+        .unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_query_bookmarks_resolves_folder_paths() {
+        let conn = places_db();
+        conn.execute_batch(
+            "
+            INSERT INTO moz_bookmarks VALUES (10, 2, NULL, 3, 'Work', 'work________');
+            INSERT INTO moz_bookmarks VALUES (11, 2, NULL, 10, 'Infra', 'infra_______');
+            INSERT INTO moz_places VALUES (1, 'https://grafana.example.com', 'Grafana', 0, 0, NULL);
+            INSERT INTO moz_bookmarks VALUES (20, 1, 1, 11, 'Grafana', 'grafana_____');
+            INSERT INTO moz_places VALUES (2, 'https://www.rust-lang.org', 'Rust', 0, 0, NULL);
+            INSERT INTO moz_bookmarks VALUES (21, 1, 2, 2, 'Rust', 'rust________');
+            ",
+        )
+        .unwrap();
+
+        let (bookmarks, _) = query_bookmarks(&conn).unwrap();
+        let grafana = bookmarks.iter().find(|b| b.name == "Grafana").unwrap();
+        assert_eq!(grafana.folder, "Bookmarks Toolbar/Work/Infra");
+        let rust = bookmarks.iter().find(|b| b.name == "Rust").unwrap();
+        assert_eq!(rust.folder, "Bookmarks Menu");
+    }
+
+    #[test]
+    fn test_query_bookmarks_reads_tags_and_keywords() {
+        let conn = places_db();
+        conn.execute_batch(
+            "
+            INSERT INTO moz_places VALUES (1, 'https://pagerduty.example.com', 'PagerDuty', 0, 0, NULL);
+            INSERT INTO moz_bookmarks VALUES (20, 1, 1, 3, 'PagerDuty', 'pagerduty___');
+            INSERT INTO moz_bookmarks VALUES (30, 2, NULL, 4, 'oncall', 'tag_oncall__');
+            INSERT INTO moz_bookmarks VALUES (31, 1, 1, 30, NULL, 'tagentry____');
+            INSERT INTO moz_bookmarks VALUES (32, 2, NULL, 4, 'alerts', 'tag_alerts__');
+            INSERT INTO moz_bookmarks VALUES (33, 1, 1, 32, NULL, 'tagentry2___');
+            INSERT INTO moz_keywords VALUES (1, 'pd', 1);
+            ",
+        )
+        .unwrap();
+
+        let (bookmarks, _) = query_bookmarks(&conn).unwrap();
+        assert_eq!(bookmarks.len(), 1);
+        assert_eq!(bookmarks[0].tags, vec!["alerts", "oncall"]);
+        assert_eq!(bookmarks[0].keyword.as_deref(), Some("pd"));
+    }
+
+    #[test]
+    fn test_query_history_orders_by_last_visit() {
+        let conn = places_db();
+        conn.execute_batch(
+            "
+            INSERT INTO moz_places VALUES (1, 'https://old.example.com', 'Old', 1, 0, NULL);
+            INSERT INTO moz_places VALUES (2, 'https://new.example.com', '', 2, 0, NULL);
+            INSERT INTO moz_historyvisits VALUES (1, 1, 1600000000000000);
+            INSERT INTO moz_historyvisits VALUES (2, 2, 1700000000000000);
+            INSERT INTO moz_historyvisits VALUES (3, 2, 1650000000000000);
+            ",
+        )
+        .unwrap();
+
+        let history = query_history(&conn).unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].title, "https://new.example.com");
+        assert_eq!(history[0].visit_count, 2);
+        assert_eq!(history[0].last_visit, Some(1700000000));
+        assert_eq!(history[1].title, "Old");
+    }
+
+    #[test]
+    fn test_query_bookmarks_tolerates_odd_rows() {
+        let conn = places_db();
+        conn.execute_batch(
+            "
+            INSERT INTO moz_places VALUES (1, 'https://untitled.example.com', NULL, 0, 0, NULL);
+            INSERT INTO moz_bookmarks VALUES (20, 1, 1, 2, NULL, 'untitled____');
+            INSERT INTO moz_places VALUES (2, 'https://page.example.com', 'Page', 0, 0, NULL);
+            INSERT INTO moz_bookmarks VALUES (21, 1, 2, 2, '', 'pagetitle___');
+            INSERT INTO moz_places VALUES (3, 'place:sort=8&maxResults=10', NULL, 0, 0, NULL);
+            INSERT INTO moz_bookmarks VALUES (22, 1, 3, 2, 'Most Visited', 'smartquery__');
+            INSERT INTO moz_places VALUES (4, NULL, 'Broken', 0, 0, NULL);
+            INSERT INTO moz_bookmarks VALUES (23, 1, 4, 2, 'Broken', 'broken______');
+            INSERT INTO moz_bookmarks VALUES (30, 2, NULL, NULL, 'Orphan', 'orphan______');
+            INSERT INTO moz_bookmarks VALUES (31, 2, NULL, 4, 'alerts', 'tagalerts___');
+            INSERT INTO moz_bookmarks VALUES (32, 1, NULL, 31, NULL, 'tagentry____');
+            ",
+        )
+        .unwrap();
+
+        let (bookmarks, skipped) = query_bookmarks(&conn).unwrap();
+        let names: Vec<&str> = bookmarks.iter().map(|b| b.name.as_str()).collect();
+        assert_eq!(names, vec!["https://untitled.example.com", "Page"]);
+        // The bookmark without a URL, the folder without a parent and the tag entry without a
+        // place.
+        assert_eq!(skipped, 3);
+    }
+}
+
+#[test]
+/// Tests that every Gecko-based browser has an entry in the registry.
+fn test_gecko_registry_is_complete() {
+    for browser in [
+        Browser::Firefox,
+        Browser::LibreWolf,
+        Browser::Waterfox,
+        Browser::Floorp,
+        Browser::Zen,
+    ] {
+        assert!(GECKO_BROWSERS.iter().any(|b| b.browser == browser));
     }
-    Ok(bookmarks)
 }
@@ -0,0 +1,374 @@
+//! GVDB Reader
+//!
+//! A minimal reader for GVDB files, the simple on-disk hash table format of GLib applications
+//! like GNOME Web. A GVDB file holds nested hash tables whose values are serialized GVariants.
+//!
+//! Only what importing bookmarks needs is supported: little-endian files, nested tables (`H`
+//! items) and variant values (`v` items) made of numbers, booleans, strings, arrays and tuples.
+//!
+//! # Errors
+//! Returns an error if the file is not a little-endian GVDB file or its content is out of bounds.
+
+use anyhow::{bail, Result};
+
+/// Signature at the start of every little-endian GVDB file.
+const SIGNATURE: &[u8] = b"GVariant";
+
+/// Size of a hash item in a table, in bytes.
+const ITEM_SIZE: usize = 24;
+
+/// Parent index of items without a parent.
+const NO_PARENT: u32 = u32::MAX;
+
+/// A decoded GVariant value.
+#[derive(PartialEq, Debug, Clone)]
+pub(crate) enum Value {
+    Bool(bool),
+    /// Any integer type, converted to `i64`.
+    Int(i64),
+    Double(f64),
+    /// A string, object path or signature.
+    Str(String),
+    Array(Vec<Value>),
+    /// A tuple or dictionary entry.
+    Tuple(Vec<Value>),
+}
+
+/// A hash table inside a GVDB file.
+pub(crate) struct Table<'a> {
+    file: &'a [u8],
+    items: Vec<Item>,
+}
+
+/// An entry of a hash table, with a key relative to its parent and a value of the given type.
+struct Item {
+    parent: u32,
+    key: (usize, usize),
+    kind: u8,
+    value: (usize, usize),
+}
+
+/// Returns the root table of a GVDB file.
+///
+/// # Arguments
+/// - `file`: The content of the file.
+///
+/// # Returns
+/// - `Ok(Table)` with the root table.
+/// - `Err(anyhow::Error)` if the file is not a little-endian GVDB file.
+pub(crate) fn root(file: &[u8]) -> Result<Table<'_>> {
+    if file.len() < 24 || &file[..8] != SIGNATURE {
+        bail!("Not a little-endian GVDB file");
+    }
+    Table::parse(
+        file,
+        read_u32(file, 16)? as usize,
+        read_u32(file, 20)? as usize,
+    )
+}
+
+impl<'a> Table<'a> {
+    fn parse(file: &'a [u8], start: usize, end: usize) -> Result<Table<'a>> {
+        let table = slice(file, start, end)?;
+        let bloom_words = (read_u32(table, 0)? & ((1 << 27) - 1)) as usize;
+        let buckets = read_u32(table, 4)? as usize;
+        let items_start = 8 + 4 * (bloom_words + buckets);
+
+        let mut items: Vec<Item> = Vec::new();
+        let mut offset = items_start;
+        while offset + ITEM_SIZE <= table.len() {
+            let key_start = read_u32(table, offset + 8)? as usize;
+            let key_size = u16::from_le_bytes([table[offset + 12], table[offset + 13]]) as usize;
+            items.push(Item {
+                parent: read_u32(table, offset + 4)?,
+                key: (key_start, key_start + key_size),
+                kind: table[offset + 14],
+                value: (
+                    read_u32(table, offset + 16)? as usize,
+                    read_u32(table, offset + 20)? as usize,
+                ),
+            });
+            offset += ITEM_SIZE;
+        }
+        Ok(Table { file, items })
+    }
+
+    /// Returns the full key of an item, including the keys of its parents.
+    fn key(&self, index: usize) -> Result<String> {
+        let mut parts: Vec<&[u8]> = Vec::new();
+        let mut current = index;
+        // Bounded by the number of items, so that a parent cycle cannot loop forever.
+        for _ in 0..self.items.len() {
+            let item = &self.items[current];
+            parts.push(slice(self.file, item.key.0, item.key.1)?);
+            if item.parent == NO_PARENT {
+                break;
+            }
+            current = item.parent as usize;
+            if current >= self.items.len() {
+                bail!("GVDB item has an invalid parent");
+            }
+        }
+        parts.reverse();
+        Ok(String::from_utf8_lossy(&parts.concat()).into_owned())
+    }
+
+    /// Returns the nested table stored under `key`, if any.
+    pub(crate) fn table(&self, key: &str) -> Result<Option<Table<'a>>> {
+        for (i, item) in self.items.iter().enumerate() {
+            if item.kind == b'H' && self.key(i)? == key {
+                return Table::parse(self.file, item.value.0, item.value.1).map(Some);
+            }
+        }
+        Ok(None)
+    }
+
+    /// Returns every variant value in the table with its key. Values that cannot be decoded
+    /// are left out.
+    pub(crate) fn values(&self) -> Result<Vec<(String, Value)>> {
+        let mut values: Vec<(String, Value)> = Vec::new();
+        for (i, item) in self.items.iter().enumerate() {
+            if item.kind != b'v' {
+                continue;
+            }
+            if let Ok(value) = parse_variant(slice(self.file, item.value.0, item.value.1)?) {
+                values.push((self.key(i)?, value));
+            }
+        }
+        Ok(values)
+    }
+}
+
+/// A GVariant type, as far as needed to decode its serialized form.
+#[derive(PartialEq, Debug, Clone)]
+enum Type {
+    Bool,
+    Int(usize),
+    Double,
+    Str,
+    Variant,
+    Array(Box<Type>),
+    Tuple(Vec<Type>),
+}
+
+impl Type {
+    /// Parses a complete type string, like `(xsas)`.
+    fn parse(signature: &str) -> Result<Type> {
+        let (t, rest) = Type::parse_one(signature.as_bytes())?;
+        if !rest.is_empty() {
+            bail!("Unsupported GVariant type {:?}", signature);
+        }
+        Ok(t)
+    }
+
+    fn parse_one(signature: &[u8]) -> Result<(Type, &[u8])> {
+        let Some((&first, rest)) = signature.split_first() else {
+            bail!("Incomplete GVariant type");
+        };
+        let t = match first {
+            b'b' => Type::Bool,
+            b'y' => Type::Int(1),
+            b'n' | b'q' => Type::Int(2),
+            b'i' | b'u' | b'h' => Type::Int(4),
+            b'x' | b't' => Type::Int(8),
+            b'd' => Type::Double,
+            b's' | b'o' | b'g' => Type::Str,
+            b'v' => Type::Variant,
+            b'a' => {
+                let (element, rest) = Type::parse_one(rest)?;
+                return Ok((Type::Array(Box::new(element)), rest));
+            }
+            b'(' | b'{' => {
+                let close = if first == b'(' { b')' } else { b'}' };
+                let mut members: Vec<Type> = Vec::new();
+                let mut rest = rest;
+                while rest.first() != Some(&close) {
+                    let (member, remainder) = Type::parse_one(rest)?;
+                    members.push(member);
+                    rest = remainder;
+                }
+                return Ok((Type::Tuple(members), &rest[1..]));
+            }
+            _ => bail!("Unsupported GVariant type {:?}", first as char),
+        };
+        Ok((t, rest))
+    }
+
+    fn alignment(&self) -> usize {
+        match self {
+            Type::Bool | Type::Str => 1,
+            Type::Int(size) => *size,
+            Type::Double | Type::Variant => 8,
+            Type::Array(element) => element.alignment(),
+            Type::Tuple(members) => members.iter().map(Type::alignment).max().unwrap_or(1),
+        }
+    }
+
+    /// Returns the size of values of this type, or `None` if it varies.
+    fn fixed_size(&self) -> Option<usize> {
+        match self {
+            Type::Bool => Some(1),
+            Type::Int(size) => Some(*size),
+            Type::Double => Some(8),
+            Type::Str | Type::Variant | Type::Array(_) => None,
+            Type::Tuple(members) => {
+                if members.is_empty() {
+                    return Some(1);
+                }
+                let mut size = 0;
+                for member in members {
+                    size = align(size, member.alignment()) + member.fixed_size()?;
+                }
+                Some(align(size, self.alignment()))
+            }
+        }
+    }
+}
+
+/// Decodes a serialized variant: the value, a nul byte, and the type string.
+fn parse_variant(data: &[u8]) -> Result<Value> {
+    let Some(separator) = data.iter().rposition(|&b| b == 0) else {
+        bail!("GVariant variant has no type");
+    };
+    let t = Type::parse(&String::from_utf8_lossy(&data[separator + 1..]))?;
+    parse_value(&t, &data[..separator])
+}
+
+/// Decodes a serialized value of the given type.
+fn parse_value(t: &Type, data: &[u8]) -> Result<Value> {
+    Ok(match t {
+        Type::Bool => Value::Bool(data.first().is_some_and(|&b| b != 0)),
+        Type::Int(size) => {
+            let bytes = slice(data, 0, *size)?;
+            let mut buffer = [0u8; 8];
+            buffer[..*size].copy_from_slice(bytes);
+            Value::Int(i64::from_le_bytes(buffer))
+        }
+        Type::Double => Value::Double(f64::from_le_bytes(slice(data, 0, 8)?.try_into()?)),
+        Type::Str => {
+            let end = data.iter().position(|&b| b == 0).unwrap_or(data.len());
+            Value::Str(String::from_utf8_lossy(&data[..end]).into_owned())
+        }
+        Type::Variant => parse_variant(data)?,
+        Type::Array(element) => Value::Array(parse_array(element, data)?),
+        Type::Tuple(members) => Value::Tuple(parse_tuple(members, data)?),
+    })
+}
+
+fn parse_array(element: &Type, data: &[u8]) -> Result<Vec<Value>> {
+    if data.is_empty() {
+        return Ok(Vec::new());
+    }
+    if let Some(size) = element.fixed_size() {
+        return data
+            .chunks_exact(size)
+            .map(|chunk| parse_value(element, chunk))
+            .collect();
+    }
+
+    // Variable-size elements are followed by a table with the end offset of every element.
+    let offset_size = offset_size(data.len());
+    let table_start = read_offset(data, data.len() - offset_size, offset_size)?;
+    if table_start > data.len() {
+        bail!("GVariant array is out of bounds");
+    }
+    let count = (data.len() - table_start) / offset_size;
+
+    let mut values: Vec<Value> = Vec::new();
+    let mut start = 0;
+    for i in 0..count {
+        let end = read_offset(data, table_start + i * offset_size, offset_size)?;
+        start = align(start, element.alignment());
+        values.push(parse_value(element, slice(data, start, end)?)?);
+        start = end;
+    }
+    Ok(values)
+}
+
+fn parse_tuple(members: &[Type], data: &[u8]) -> Result<Vec<Value>> {
+    // The end offsets of variable-size members, except the last member, are stored at the end
+    // of the tuple in reverse order.
+    let offset_size = offset_size(data.len());
+    let mut frame_end = data.len();
+    let mut values: Vec<Value> = Vec::new();
+    let mut start = 0;
+
+    for (i, member) in members.iter().enumerate() {
+        start = align(start, member.alignment());
+        let end = match member.fixed_size() {
+            Some(size) => start + size,
+            None if i == members.len() - 1 => frame_end,
+            None => {
+                frame_end = frame_end
+                    .checked_sub(offset_size)
+                    .ok_or_else(|| anyhow::anyhow!("GVariant tuple is out of bounds"))?;
+                read_offset(data, frame_end, offset_size)?
+            }
+        };
+        values.push(parse_value(member, slice(data, start, end)?)?);
+        start = end;
+    }
+    Ok(values)
+}
+
+/// Returns the size of the framing offsets in a container of the given size.
+fn offset_size(container_size: usize) -> usize {
+    match container_size {
+        0..=0xff => 1,
+        0x100..=0xffff => 2,
+        0x1_0000..=0xffff_ffff => 4,
+        _ => 8,
+    }
+}
+
+fn read_offset(data: &[u8], at: usize, size: usize) -> Result<usize> {
+    let bytes = slice(data, at, at + size)?;
+    let mut buffer = [0u8; 8];
+    buffer[..size].copy_from_slice(bytes);
+    Ok(u64::from_le_bytes(buffer) as usize)
+}
+
+fn read_u32(data: &[u8], at: usize) -> Result<u32> {
+    Ok(u32::from_le_bytes(slice(data, at, at + 4)?.try_into()?))
+}
+
+fn slice(data: &[u8], start: usize, end: usize) -> Result<&[u8]> {
+    data.get(start..end)
+        .ok_or_else(|| anyhow::anyhow!("GVDB data is out of bounds"))
+}
+
+fn align(offset: usize, alignment: usize) -> usize {
+    offset.div_ceil(alignment) * alignment
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_tuple_variant() {
+        // (xsas) holding (42, "Rust", ["lang", "docs"]), followed by its type.
+        let mut data: Vec<u8> = Vec::new();
+        data.extend(42i64.to_le_bytes());
+        data.extend(b"Rust\0");
+        let string_end = data.len() as u8;
+        // Array elements followed by their end offsets.
+        data.extend(b"lang\0docs\0");
+        data.extend([5, 10]);
+        // End offset of the string, the only variable-size member before the last one.
+        data.push(string_end);
+        data.extend(b"\0(xsas)");
+
+        assert_eq!(
+            parse_variant(&data).unwrap(),
+            Value::Tuple(vec![
+                Value::Int(42),
+                Value::Str("Rust".to_string()),
+                Value::Array(vec![
+                    Value::Str("lang".to_string()),
+                    Value::Str("docs".to_string())
+                ]),
+            ])
+        );
+    }
+}
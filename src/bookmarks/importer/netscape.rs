@@ -0,0 +1,212 @@
+//! Netscape Bookmark File Importer
+//!
+//! This module provides functionality to import bookmarks from an HTML file in the
+//! `NETSCAPE-Bookmark-file-1` format, which every major browser can export.
+//!
+//! The format is loosely structured HTML rather than XML, so it is read with a small tag scanner
+//! instead of an XML parser. Folders are `<H3>` headings followed by a `<DL>` list, and bookmarks
+//! are `<A>` links whose `ADD_DATE`, `TAGS` and `SHORTCUTURL` attributes are imported as well.
+//!
+//! # Errors
+//! Returns an error if the file cannot be read.
+
+use std::{fs, path::Path};
+
+use anyhow::{Context, Result};
+
+use super::{Bookmark, Browser, Origin};
+
+/// Imports bookmarks from a Netscape bookmark file.
+///
+/// # Arguments
+/// - `path`: Path to the HTML file.
+///
+/// # Returns
+/// - `Ok(Vec<Bookmark>)` on success.
+/// - `Err(anyhow::Error)` if the file cannot be read.
+pub(crate) fn import(path: &Path) -> Result<Vec<Bookmark>> {
+    let content = fs::read_to_string(path).with_context(|| format!("Failed to read {:?}", path))?;
+
+    let bookmarks = parse(&content)
+        .into_iter()
+        .map(|mut bookmark| {
+            bookmark.origins = vec![Origin::new(Browser::Html, None)];
+            bookmark
+        })
+        .collect();
+    Ok(bookmarks)
+}
+
+/// Parses the content of a Netscape bookmark file.
+///
+/// # Arguments
+/// - `content`: The HTML content of the file.
+///
+/// # Returns
+/// - `Vec<Bookmark>` with all bookmarks found, in document order.
+fn parse(content: &str) -> Vec<Bookmark> {
+    let mut bookmarks: Vec<Bookmark> = Vec::new();
+    // One entry per open `<DL>`, holding the folder name it belongs to, if any.
+    let mut folders: Vec<Option<String>> = Vec::new();
+    let mut pending_folder: Option<String> = None;
+    let mut rest = content;
+
+    while let Some(start) = rest.find('<') {
+        let Some(end) = rest[start..].find('>') else {
+            break;
+        };
+        let tag = &rest[start + 1..start + end];
+        rest = &rest[start + end + 1..];
+
+        let (name, attributes) = tag.split_once(char::is_whitespace).unwrap_or((tag, ""));
+        match name.to_ascii_uppercase().as_str() {
+            "H3" => {
+                let (text, remainder) = read_text_until(rest, "</H3>");
+                pending_folder = Some(text);
+                rest = remainder;
+            }
+            "DL" => folders.push(pending_folder.take()),
+            "/DL" => {
+                folders.pop();
+            }
+            "A" => {
+                let (text, remainder) = read_text_until(rest, "</A>");
+                rest = remainder;
+
+                let attributes = parse_attributes(attributes);
+                let Some(url) = attribute(&attributes, "HREF") else {
+                    continue;
+                };
+
+                let mut bookmark = Bookmark::new(&text, url);
+                bookmark.folder = folders
+                    .iter()
+                    .flatten()
+                    .cloned()
+                    .collect::<Vec<_>>()
+                    .join("/");
+                bookmark.date_added =
+                    attribute(&attributes, "ADD_DATE").and_then(|d| d.parse().ok());
+                bookmark.tags = attribute(&attributes, "TAGS")
+                    .map(|tags| {
+                        tags.split(',')
+                            .map(str::trim)
+                            .filter(|t| !t.is_empty())
+                            .map(str::to_string)
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                bookmark.keyword = attribute(&attributes, "SHORTCUTURL").map(str::to_string);
+                bookmarks.push(bookmark);
+            }
+            _ => {}
+        }
+    }
+
+    bookmarks
+}
+
+/// Reads the text up to a closing tag, matched case-insensitively.
+///
+/// # Returns
+/// - The decoded text and the remaining input after the closing tag.
+fn read_text_until<'a>(input: &'a str, closing_tag: &str) -> (String, &'a str) {
+    let end = input
+        .as_bytes()
+        .windows(closing_tag.len())
+        .position(|window| window.eq_ignore_ascii_case(closing_tag.as_bytes()))
+        .unwrap_or(input.len());
+    let text = decode_entities(input[..end].trim());
+    let remainder = &input[(end + closing_tag.len()).min(input.len())..];
+    (text, remainder)
+}
+
+/// Parses the attributes of a tag, like `HREF="https://example.com" ADD_DATE="1700000000"`.
+///
+/// # Returns
+/// - `Vec<(String, String)>` with uppercased attribute names and decoded values.
+fn parse_attributes(input: &str) -> Vec<(String, String)> {
+    let mut attributes: Vec<(String, String)> = Vec::new();
+    let mut rest = input.trim();
+
+    while let Some(eq) = rest.find('=') {
+        let name = rest[..eq].trim().to_ascii_uppercase();
+        let after = rest[eq + 1..].trim_start();
+
+        let (value, remainder) = match after.chars().next() {
+            Some(quote @ ('"' | '\'')) => {
+                let value_end = after[1..].find(quote).map_or(after.len(), |i| i + 1);
+                (
+                    &after[1..value_end],
+                    &after[(value_end + 1).min(after.len())..],
+                )
+            }
+            _ => {
+                let value_end = after.find(char::is_whitespace).unwrap_or(after.len());
+                (&after[..value_end], &after[value_end..])
+            }
+        };
+
+        attributes.push((name, decode_entities(value)));
+        rest = remainder.trim_start();
+    }
+
+    attributes
+}
+
+/// Returns the value of the named attribute, if present.
+fn attribute<'a>(attributes: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    attributes
+        .iter()
+        .find(|(n, _)| n == name)
+        .map(|(_, v)| v.as_str())
+}
+
+/// Decodes the HTML entities that browsers use when exporting bookmarks.
+fn decode_entities(input: &str) -> String {
+    input
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&#x27;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_netscape_file() {
+        let content = r#"<!DOCTYPE NETSCAPE-Bookmark-file-1>
+<META HTTP-EQUIV="Content-Type" CONTENT="text/html; charset=UTF-8">
+<TITLE>Bookmarks</TITLE>
+<H1>Bookmarks</H1>
+<DL><p>
+    <DT><H3 ADD_DATE="1700000000">Work</H3>
+    <DL><p>
+        <DT><H3>Infra</H3>
+        <DL><p>
+            <DT><A HREF="https://grafana.example.com/?a=1&amp;b=2" ADD_DATE="1700000001" TAGS="oncall,metrics">Grafana &amp; Co</A>
+        </DL><p>
+    </DL><p>
+    <DT><a href="https://www.rust-lang.org" shortcuturl="rs">Rust</a>
+</DL><p>
+"#;
+
+        let bookmarks = parse(content);
+        assert_eq!(bookmarks.len(), 2);
+
+        let grafana = &bookmarks[0];
+        assert_eq!(grafana.name, "Grafana & Co");
+        assert_eq!(grafana.url, "https://grafana.example.com/?a=1&b=2");
+        assert_eq!(grafana.folder, "Work/Infra");
+        assert_eq!(grafana.date_added, Some(1700000001));
+        assert_eq!(grafana.tags, vec!["oncall", "metrics"]);
+
+        let rust = &bookmarks[1];
+        assert_eq!(rust.folder, "");
+        assert_eq!(rust.keyword.as_deref(), Some("rs"));
+    }
+}
@@ -0,0 +1,135 @@
+//! Browser Profiles
+//!
+//! Shared handling of browser profiles for the importers of browsers that support several
+//! profiles. Each importer discovers its profiles in its own way and uses [`select`] to pick the
+//! ones named in the configuration.
+
+use std::{collections::HashSet, path::PathBuf};
+
+use anyhow::Result;
+
+use super::{Browser, ALL_PROFILES};
+
+/// A browser profile on disk.
+#[derive(Debug, Clone)]
+pub(crate) struct Profile {
+    /// Directory name of the profile, e.g. `Profile 1` or `abcd1234.default-release`.
+    pub dir_name: String,
+    /// Display name of the profile, e.g. `Work`.
+    pub name: String,
+    /// Full path to the profile directory.
+    pub path: PathBuf,
+}
+
+impl Profile {
+    /// Returns true if `wanted` names this profile by display name or directory name.
+    pub fn matches(&self, wanted: &str) -> bool {
+        self.name.eq_ignore_ascii_case(wanted) || self.dir_name.eq_ignore_ascii_case(wanted)
+    }
+}
+
+/// Picks the profiles named in `selection` out of the `available` profiles.
+///
+/// # Arguments
+/// - `browser`: The browser the profiles belong to, used in error messages.
+/// - `available`: All profiles of the browser, with the default profile first.
+/// - `selection`: Profile names or directories to select. An empty selection means the default
+///   profile and [`ALL_PROFILES`] means every profile.
+///
+/// # Returns
+/// - `Ok(Vec<Profile>)` with the selected profiles, each only once and in the order they were
+///   first named.
+/// - `Err(anyhow::Error)` if the browser has no profiles or a named profile does not exist.
+pub(crate) fn select(
+    browser: &Browser,
+    available: Vec<Profile>,
+    selection: &[String],
+) -> Result<Vec<Profile>> {
+    if available.is_empty() {
+        return Err(anyhow::anyhow!(
+            "No profiles found for {:?}. Is it installed?",
+            browser
+        ));
+    }
+
+    if selection.is_empty() {
+        return Ok(available.into_iter().take(1).collect());
+    }
+
+    if selection.iter().any(|s| s == ALL_PROFILES) {
+        return Ok(available);
+    }
+
+    let mut selected: Vec<Profile> = Vec::new();
+    let mut seen: HashSet<PathBuf> = HashSet::new();
+    for wanted in selection {
+        let matching: Vec<&Profile> = available.iter().filter(|p| p.matches(wanted)).collect();
+        if matching.is_empty() {
+            let names: Vec<&str> = available.iter().map(|p| p.name.as_str()).collect();
+            return Err(anyhow::anyhow!(
+                "Profile {:?} not found for {:?}. Available profiles: {}",
+                wanted,
+                browser,
+                names.join(", ")
+            ));
+        }
+        for profile in matching {
+            if seen.insert(profile.path.clone()) {
+                selected.push(profile.clone());
+            }
+        }
+    }
+    Ok(selected)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profiles() -> Vec<Profile> {
+        vec![
+            Profile {
+                dir_name: "Default".to_string(),
+                name: "Personal".to_string(),
+                path: PathBuf::from("/tmp/Default"),
+            },
+            Profile {
+                dir_name: "Profile 1".to_string(),
+                name: "Work".to_string(),
+                path: PathBuf::from("/tmp/Profile 1"),
+            },
+        ]
+    }
+
+    #[test]
+    fn test_select_default_profile() {
+        let selected = select(&Browser::Chrome, profiles(), &[]).unwrap();
+        assert_eq!(selected.len(), 1);
+        assert_eq!(selected[0].name, "Personal");
+    }
+
+    #[test]
+    fn test_select_by_name_and_directory() {
+        let selection = vec!["work".to_string(), "Default".to_string()];
+        let selected = select(&Browser::Chrome, profiles(), &selection).unwrap();
+        let names: Vec<&str> = selected.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["Work", "Personal"]);
+    }
+
+    #[test]
+    fn test_select_skips_repeated_profiles() {
+        let selection = ["Work", "Default", "Profile 1", "work"].map(str::to_string);
+        let selected = select(&Browser::Chrome, profiles(), &selection).unwrap();
+        let names: Vec<&str> = selected.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["Work", "Personal"]);
+    }
+
+    #[test]
+    fn test_select_all_and_unknown_profiles() {
+        let all = select(&Browser::Chrome, profiles(), &[ALL_PROFILES.to_string()]).unwrap();
+        assert_eq!(all.len(), 2);
+
+        let unknown = select(&Browser::Chrome, profiles(), &["Gaming".to_string()]);
+        assert!(unknown.is_err());
+    }
+}
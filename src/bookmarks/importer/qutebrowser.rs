@@ -0,0 +1,145 @@
+//! qutebrowser Bookmarks Importer
+//!
+//! This module provides functionality to import bookmarks and quickmarks from qutebrowser. Both
+//! are plain text files in qutebrowser's config directory:
+//! - `bookmarks/urls` has one bookmark per line, the URL followed by the title.
+//! - `quickmarks` has one quickmark per line, the name followed by the URL. Names may contain
+//!   spaces, so the URL is the last word of the line.
+//!
+//! Quickmarks are imported into a `Quickmarks` folder, with the first word of their name as
+//! keyword, as keywords are matched against the first word of the query.
+//!
+//! # Supported Platforms
+//! - Linux: Native installs under `~/.config/` and Flatpak installs under `~/.var/app/`.
+//! - Windows: Installs under `AppData/Roaming/`.
+//!
+//! # Errors
+//! Returns an error if the config directory cannot be found, or if a file exists but cannot be
+//! read.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+
+use super::{Bookmark, Browser, Origin};
+
+/// Known config directories of qutebrowser on Linux, relative to the home directory.
+const LINUX_CONFIG_DIRS: &[&str] = &[
+    ".config/qutebrowser",
+    ".var/app/org.qutebrowser.qutebrowser/config/qutebrowser",
+];
+
+/// Known config directories of qutebrowser on Windows, relative to the home directory.
+const WINDOWS_CONFIG_DIRS: &[&str] = &["AppData/Roaming/qutebrowser/config"];
+
+/// Folder that quickmarks are imported into.
+const QUICKMARKS_FOLDER: &str = "Quickmarks";
+
+/// Imports all bookmarks and quickmarks from qutebrowser.
+///
+/// # Returns
+/// - `Ok(Vec<Bookmark>)` with the bookmarks, followed by the quickmarks.
+/// - `Err(anyhow::Error)` if qutebrowser's config directory cannot be found, or a file cannot
+///   be read.
+pub(crate) fn import() -> Result<Vec<Bookmark>> {
+    let [urls_path, quickmarks_path] = source_files()?;
+    let origin = Origin::new(Browser::Qutebrowser, None);
+
+    let mut bookmarks = parse_urls(&read_if_exists(&urls_path)?);
+    bookmarks.extend(parse_quickmarks(&read_if_exists(&quickmarks_path)?));
+    for bookmark in &mut bookmarks {
+        bookmark.origins = vec![origin.clone()];
+    }
+    Ok(bookmarks)
+}
+
+/// Returns the paths of the bookmarks and quickmarks files in qutebrowser's config directory.
+///
+/// # Returns
+/// - `Ok([PathBuf; 2])` with the path of `bookmarks/urls` and of `quickmarks`.
+/// - `Err(anyhow::Error)` if no config directory exists or the OS is unsupported.
+pub(crate) fn source_files() -> Result<[PathBuf; 2]> {
+    let candidates = if cfg!(target_os = "windows") {
+        WINDOWS_CONFIG_DIRS
+    } else if cfg!(target_os = "linux") {
+        LINUX_CONFIG_DIRS
+    } else {
+        return Err(anyhow::anyhow!("Unsupported OS for importing bookmarks"));
+    };
+
+    let home_dir =
+        dirs::home_dir().ok_or_else(|| anyhow::anyhow!("Could not determine home directory."))?;
+    let config_dir = candidates
+        .iter()
+        .map(|relative| home_dir.join(relative))
+        .find(|path| path.is_dir())
+        .ok_or_else(|| anyhow::anyhow!("No config found for qutebrowser. Is it installed?"))?;
+
+    Ok([
+        config_dir.join("bookmarks").join("urls"),
+        config_dir.join("quickmarks"),
+    ])
+}
+
+/// Reads a file, treating a missing file as empty.
+fn read_if_exists(path: &Path) -> Result<String> {
+    if !path.exists() {
+        return Ok(String::new());
+    }
+    fs::read_to_string(path).with_context(|| format!("Failed to read {:?}", path))
+}
+
+/// Parses the content of a `bookmarks/urls` file, with lines like `URL TITLE`.
+fn parse_urls(content: &str) -> Vec<Bookmark> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let (url, title) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+            let title = title.trim();
+            Bookmark::new(if title.is_empty() { url } else { title }, url)
+        })
+        .collect()
+}
+
+/// Parses the content of a `quickmarks` file, with lines like `NAME URL`.
+fn parse_quickmarks(content: &str) -> Vec<Bookmark> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter_map(|line| line.rsplit_once(char::is_whitespace))
+        .map(|(name, url)| {
+            let name = name.trim();
+            let mut bookmark = Bookmark::new(name, url);
+            bookmark.folder = QUICKMARKS_FOLDER.to_string();
+            bookmark.keyword = name.split_whitespace().next().map(str::to_string);
+            bookmark
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_urls_and_quickmarks() {
+        let bookmarks =
+            parse_urls("https://www.rust-lang.org Rust Programming Language\n\nhttps://docs.rs\n");
+        assert_eq!(bookmarks.len(), 2);
+        assert_eq!(bookmarks[0].name, "Rust Programming Language");
+        assert_eq!(bookmarks[0].url, "https://www.rust-lang.org");
+        assert_eq!(bookmarks[1].name, "https://docs.rs");
+
+        let quickmarks = parse_quickmarks("crates io https://crates.io\n");
+        assert_eq!(quickmarks.len(), 1);
+        assert_eq!(quickmarks[0].name, "crates io");
+        assert_eq!(quickmarks[0].url, "https://crates.io");
+        assert_eq!(quickmarks[0].folder, "Quickmarks");
+        assert_eq!(quickmarks[0].keyword.as_deref(), Some("crates"));
+    }
+}
@@ -0,0 +1,170 @@
+//! Database Snapshots
+//!
+//! Browsers keep their SQLite databases locked while they are running, so the importers read
+//! from a temporary copy instead of the live file.
+//!
+//! Recent changes may not have reached the database file yet: in WAL mode they sit in the
+//! `-wal` file next to it, and an interrupted transaction leaves a `-journal` file behind. These
+//! side files are copied along with the database, so SQLite applies (or rolls back) them when the
+//! copy is opened and the snapshot matches what the browser sees.
+//!
+//! Copying several files is not atomic, so the modification time and size of every file are
+//! checked again after copying. If the browser wrote to any of them in the meantime, the copy
+//! is made again, up to [`COPY_ATTEMPTS`] times.
+//!
+//! Every snapshot lives in its own temporary directory, so concurrent instances of hhand never
+//! overwrite each other's copy, and the directory is deleted when the snapshot is dropped.
+
+use std::{
+    ffi::OsString,
+    fs,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use anyhow::{Context, Result};
+use rusqlite::Connection;
+use tempfile::TempDir;
+
+/// Suffixes of the files SQLite keeps next to a database with uncommitted or uncheckpointed data.
+const SIDE_FILE_SUFFIXES: &[&str] = &["-wal", "-journal"];
+
+/// How often copying is tried before giving up on a database that keeps changing.
+const COPY_ATTEMPTS: usize = 3;
+
+/// A temporary copy of a database, deleted when dropped.
+pub(crate) struct Snapshot {
+    // Held so the directory and the copy inside it live as long as the snapshot.
+    _dir: TempDir,
+    path: PathBuf,
+}
+
+impl Snapshot {
+    /// Copies a database and its side files into a new temporary directory.
+    ///
+    /// # Arguments
+    /// - `path`: Path to the database file.
+    ///
+    /// # Returns
+    /// - `Ok(Snapshot)` with the copy.
+    /// - `Err(anyhow::Error)` if the file does not exist, cannot be copied, or changed during
+    ///   every attempt to copy it.
+    pub(crate) fn create(path: &Path) -> Result<Snapshot> {
+        if !path.is_file() {
+            return Err(anyhow::anyhow!("File {:?} was not found.", path));
+        }
+        let file_name = path
+            .file_name()
+            .ok_or_else(|| anyhow::anyhow!("Invalid database path {:?}", path))?;
+
+        let dir = tempfile::Builder::new()
+            .prefix("hhand-")
+            .tempdir()
+            .context("Failed to create a temporary directory")?;
+        let copy_path = dir.path().join(file_name);
+
+        for _ in 0..COPY_ATTEMPTS {
+            let before = stamps(path);
+            copy_files(path, &copy_path)?;
+            if stamps(path) == before {
+                return Ok(Snapshot {
+                    _dir: dir,
+                    path: copy_path,
+                });
+            }
+        }
+        Err(anyhow::anyhow!(
+            "{:?} kept changing while being copied",
+            path
+        ))
+    }
+
+    /// Opens a connection to the copied database.
+    ///
+    /// The connection is opened read-write, as SQLite has to be able to apply the copied side
+    /// files. Only the copy is ever changed.
+    pub(crate) fn open(&self) -> Result<Connection> {
+        let conn = Connection::open(&self.path)
+            .with_context(|| format!("Failed to open {:?}", self.path))?;
+        Ok(conn)
+    }
+}
+
+/// Copies a database and its side files, replacing any earlier copy.
+fn copy_files(path: &Path, copy_path: &Path) -> Result<()> {
+    // The database is copied first, so side files are never older than the copied database.
+    fs::copy(path, copy_path).with_context(|| format!("Failed to copy {:?}", path))?;
+    for suffix in SIDE_FILE_SUFFIXES {
+        let side_path = with_suffix(path, suffix);
+        let side_copy_path = with_suffix(copy_path, suffix);
+        if side_path.is_file() {
+            fs::copy(&side_path, &side_copy_path)
+                .with_context(|| format!("Failed to copy {:?}", side_path))?;
+        } else if side_copy_path.is_file() {
+            // A side file that is gone by now must not be applied to a newer copy.
+            fs::remove_file(&side_copy_path)
+                .with_context(|| format!("Failed to remove {:?}", side_copy_path))?;
+        }
+    }
+    Ok(())
+}
+
+/// Returns the modification time and size of a database and its side files, `None` for files
+/// that are missing.
+fn stamps(path: &Path) -> Vec<Option<(SystemTime, u64)>> {
+    std::iter::once(path.to_path_buf())
+        .chain(
+            SIDE_FILE_SUFFIXES
+                .iter()
+                .map(|suffix| with_suffix(path, suffix)),
+        )
+        .map(|file| {
+            let metadata = fs::metadata(file).ok()?;
+            Some((metadata.modified().ok()?, metadata.len()))
+        })
+        .collect()
+}
+
+/// Appends a suffix to the file name of a path, e.g. `places.sqlite` to `places.sqlite-wal`.
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = OsString::from(path.as_os_str());
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_includes_wal() {
+        let source_dir = tempfile::tempdir().unwrap();
+        let db_path = source_dir.path().join("places.sqlite");
+
+        let conn = Connection::open(&db_path).unwrap();
+        conn.execute_batch(
+            "
+            PRAGMA journal_mode = WAL;
+            PRAGMA wal_autocheckpoint = 0;
+            CREATE TABLE moz_places (url TEXT);
+            INSERT INTO moz_places VALUES ('https://www.rust-lang.org');
+            ",
+        )
+        .unwrap();
+        // The connection stays open, so the insert is still only in the WAL file.
+        assert!(with_suffix(&db_path, "-wal").is_file());
+
+        let snapshot = Snapshot::create(&db_path).unwrap();
+        let snapshot_dir = snapshot.path.parent().unwrap().to_path_buf();
+        let count: i64 = snapshot
+            .open()
+            .unwrap()
+            .query_row("SELECT COUNT(*) FROM moz_places", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 1);
+
+        drop(snapshot);
+        assert!(!snapshot_dir.exists());
+        drop(conn);
+    }
+}
@@ -0,0 +1,209 @@
+//! XBEL Bookmarks Importer
+//!
+//! This module provides functionality to import bookmarks from XBEL files, the XML Bookmark
+//! Exchange Language. XBEL is the native format of Konqueror, and can be exported by Falkon and
+//! other browsers.
+//!
+//! Folders are `<folder>` elements and bookmarks are `<bookmark>` elements with an `href`
+//! attribute, both with a `<title>` element. The `<desc>` of a bookmark, and its `id` and
+//! `added` attributes are imported as well. Aliases, separators and metadata are ignored.
+//!
+//! # Errors
+//! Returns an error if the file cannot be found, read or parsed.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use quick_xml::{
+    events::{BytesStart, Event},
+    Reader,
+};
+
+use super::{Bookmark, Browser, Origin};
+use crate::bookmarks::from_iso8601;
+
+/// Known locations of Konqueror's bookmarks file, relative to the home directory.
+const KONQUEROR_PATHS: &[&str] = &[
+    ".local/share/konqueror/bookmarks.xml",
+    ".var/app/org.kde.konqueror/data/konqueror/bookmarks.xml",
+];
+
+/// Imports bookmarks from an XBEL file.
+///
+/// # Arguments
+/// - `path`: Path to the XBEL file.
+/// - `browser`: The browser the file belongs to, recorded as the origin of the bookmarks.
+///
+/// # Returns
+/// - `Ok(Vec<Bookmark>)` on success.
+/// - `Err(anyhow::Error)` if the file cannot be read or parsed.
+pub(crate) fn import(path: &Path, browser: Browser) -> Result<Vec<Bookmark>> {
+    let content = fs::read_to_string(path).with_context(|| format!("Failed to read {:?}", path))?;
+    let origin = Origin::new(browser, None);
+
+    let bookmarks = parse(&content)
+        .with_context(|| format!("Failed to parse XBEL bookmarks at {:?}", path))?
+        .into_iter()
+        .map(|mut bookmark| {
+            bookmark.origins = vec![origin.clone()];
+            bookmark
+        })
+        .collect();
+    Ok(bookmarks)
+}
+
+/// Returns the path of Konqueror's bookmarks file.
+///
+/// # Returns
+/// - `Ok(PathBuf)` with the first known location that exists.
+/// - `Err(anyhow::Error)` if the OS is unsupported or no bookmarks file exists.
+pub(crate) fn konqueror_path() -> Result<PathBuf> {
+    if !cfg!(target_os = "linux") {
+        return Err(anyhow::anyhow!("Unsupported OS for importing bookmarks"));
+    }
+    let home_dir =
+        dirs::home_dir().ok_or_else(|| anyhow::anyhow!("Could not determine home directory."))?;
+    KONQUEROR_PATHS
+        .iter()
+        .map(|relative| home_dir.join(relative))
+        .find(|path| path.is_file())
+        .ok_or_else(|| anyhow::anyhow!("No bookmarks found for Konqueror. Is it installed?"))
+}
+
+/// The element whose text is currently being read.
+enum Field {
+    FolderTitle,
+    BookmarkTitle,
+    BookmarkDesc,
+}
+
+/// Parses the content of an XBEL file.
+///
+/// # Returns
+/// - `Ok(Vec<Bookmark>)` with all bookmarks found, in document order.
+/// - `Err(anyhow::Error)` if the content is not well-formed XML.
+fn parse(content: &str) -> Result<Vec<Bookmark>> {
+    let mut reader = Reader::from_str(content);
+    reader.config_mut().trim_text(true);
+
+    let mut bookmarks: Vec<Bookmark> = Vec::new();
+    // Titles of the open folders, empty until their `<title>` has been read.
+    let mut folders: Vec<String> = Vec::new();
+    let mut current: Option<Bookmark> = None;
+    let mut field: Option<Field> = None;
+
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) => match e.name().as_ref() {
+                b"folder" => folders.push(String::new()),
+                b"bookmark" => current = read_bookmark(&e, &folders)?,
+                b"title" if current.is_some() => field = Some(Field::BookmarkTitle),
+                b"title" if !folders.is_empty() => field = Some(Field::FolderTitle),
+                b"desc" if current.is_some() => field = Some(Field::BookmarkDesc),
+                _ => {}
+            },
+            Event::Empty(e) if e.name().as_ref() == b"bookmark" => {
+                bookmarks.extend(read_bookmark(&e, &folders)?);
+            }
+            Event::Text(e) => {
+                let text = e.unescape()?;
+                match (&field, &mut current) {
+                    (Some(Field::BookmarkTitle), Some(bookmark)) => bookmark.name = text.into(),
+                    (Some(Field::BookmarkDesc), Some(bookmark)) => {
+                        bookmark.description = Some(text.into())
+                    }
+                    (Some(Field::FolderTitle), _) => {
+                        if let Some(folder) = folders.last_mut() {
+                            *folder = text.into();
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Event::End(e) => match e.name().as_ref() {
+                b"folder" => {
+                    folders.pop();
+                }
+                b"bookmark" => bookmarks.extend(current.take()),
+                b"title" | b"desc" => field = None,
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok(bookmarks)
+}
+
+/// Creates a bookmark from the attributes of a `<bookmark>` element, named after its URL until
+/// its title is read.
+///
+/// # Returns
+/// - `Ok(Some(Bookmark))` if the element has an `href` attribute.
+/// - `Ok(None)` if it has none.
+/// - `Err(anyhow::Error)` if an attribute is malformed.
+fn read_bookmark(element: &BytesStart, folders: &[String]) -> Result<Option<Bookmark>> {
+    let attribute = |name: &str| -> Result<Option<String>> {
+        Ok(match element.try_get_attribute(name)? {
+            Some(attribute) => Some(attribute.unescape_value()?.into_owned()),
+            None => None,
+        })
+    };
+
+    let Some(url) = attribute("href")? else {
+        return Ok(None);
+    };
+    let mut bookmark = Bookmark::new(&url, &url);
+    bookmark.folder = folders
+        .iter()
+        .filter(|f| !f.is_empty())
+        .cloned()
+        .collect::<Vec<_>>()
+        .join("/");
+    bookmark.guid = attribute("id")?;
+    bookmark.date_added = attribute("added")?.and_then(|added| from_iso8601(&added));
+    Ok(Some(bookmark))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_xbel_file() {
+        let content = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE xbel>
+<xbel version="1.0">
+    <folder>
+        <title>Work &amp; Play</title>
+        <folder>
+            <title>Infra</title>
+            <bookmark href="https://grafana.example.com" added="2023-10-31T12:00:00Z" id="42">
+                <title>Grafana</title>
+                <desc>Dashboards</desc>
+            </bookmark>
+        </folder>
+        <separator/>
+    </folder>
+    <bookmark href="https://docs.rs"/>
+</xbel>
+"#;
+
+        let bookmarks = parse(content).unwrap();
+        assert_eq!(bookmarks.len(), 2);
+
+        let grafana = &bookmarks[0];
+        assert_eq!(grafana.name, "Grafana");
+        assert_eq!(grafana.folder, "Work & Play/Infra");
+        assert_eq!(grafana.description.as_deref(), Some("Dashboards"));
+        assert_eq!(grafana.guid.as_deref(), Some("42"));
+        assert_eq!(grafana.date_added, Some(1_698_753_600));
+
+        assert_eq!(bookmarks[1].name, "https://docs.rs");
+        assert_eq!(bookmarks[1].folder, "");
+    }
+}
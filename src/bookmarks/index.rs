@@ -0,0 +1,379 @@
+//! Bookmark Index
+//!
+//! This module caches imported bookmarks and history in a local index, so that hhand can show
+//! them immediately on start instead of re-reading every browser's files first. The index is a
+//! JSON file in the user's cache directory (e.g. `~/.cache/hhand/index.json` on Linux).
+//!
+//! Each configured [`Source`] is cached together with the modification time and size of the
+//! bookmark files it was imported from. A source is only imported again once one of these files
+//! has changed. The history databases of Chromium-based browsers change on every page visit, so
+//! they are only checked every [`HISTORY_REFRESH_INTERVAL`]. Sources that fail to import are
+//! cached with their error in the same way, so a missing browser is not imported again on every
+//! check. [`spawn_watcher`] keeps checking for changes in the background, so that bookmarks added
+//! in a browser show up while hhand is open, at most once every [`RELOAD_DELAY`] per source.
+//!
+//! # Errors
+//! A missing or unreadable index is treated as empty. Saving returns an error if the cache
+//! directory cannot be determined or the index cannot be written.
+
+use std::{
+    fs::{self, File},
+    io::{BufReader, BufWriter},
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Receiver},
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use super::importer::{import_error, import_from, Import, Source};
+
+/// File name of the index inside hhand's cache directory.
+const INDEX_FILE: &str = "index.json";
+
+/// How often the watcher checks the sources for changes.
+const WATCH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How long a source is kept after an import, even if its bookmark files changed. Firefox writes
+/// its write-ahead log on every page visit, which would otherwise import it on every check.
+const RELOAD_DELAY: Duration = Duration::from_secs(30);
+
+/// How long a source is kept when only its history changed, before it is imported again.
+const HISTORY_REFRESH_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// Modification time and size of a file, used to detect changes.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+struct FileStamp {
+    path: PathBuf,
+    /// Modification time in nanoseconds since the Unix epoch, `None` if the file is missing.
+    modified: Option<u128>,
+    size: u64,
+}
+
+impl FileStamp {
+    fn new(path: &Path) -> FileStamp {
+        let metadata = fs::metadata(path).ok();
+        FileStamp {
+            path: path.to_path_buf(),
+            modified: metadata
+                .as_ref()
+                .and_then(|m| m.modified().ok())
+                .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                .map(|d| d.as_nanos()),
+            size: metadata.map_or(0, |m| m.len()),
+        }
+    }
+}
+
+/// The cached import of a single source.
+#[derive(Serialize, Deserialize, Debug)]
+struct IndexEntry {
+    source: Source,
+    /// Bookmark files of the source, see [`Source::files`].
+    files: Vec<FileStamp>,
+    /// History files of the source, see [`Source::history_files`].
+    #[serde(default)]
+    history_files: Vec<FileStamp>,
+    /// When the source was imported, in seconds since the Unix epoch.
+    #[serde(default)]
+    imported_at: u64,
+    import: Import,
+    /// Whether the import failed, in which case `import` only holds the error.
+    #[serde(default)]
+    failed: bool,
+}
+
+/// Cached imports of the configured sources.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct Index {
+    entries: Vec<IndexEntry>,
+}
+
+impl Index {
+    /// Loads the index from hhand's cache directory.
+    ///
+    /// # Returns
+    /// - The loaded [`Index`], or an empty one if it is missing or cannot be read.
+    pub fn load_default() -> Index {
+        default_path()
+            .and_then(|path| Index::load(&path))
+            .unwrap_or_default()
+    }
+
+    fn load(path: &Path) -> Result<Index> {
+        let reader = BufReader::new(File::open(path)?);
+        Ok(serde_json::from_reader(reader)?)
+    }
+
+    /// Saves the index to hhand's cache directory.
+    ///
+    /// # Returns
+    /// - `Ok(())` on success.
+    /// - `Err(anyhow::Error)` if the cache directory cannot be determined or written to.
+    pub fn save_default(&self) -> Result<()> {
+        self.save(&default_path()?)
+    }
+
+    fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer(writer, self)?;
+        Ok(())
+    }
+
+    /// Returns the cached import of the given sources, without checking for changes.
+    ///
+    /// # Returns
+    /// - `Some(Import)` with the merged cached entries, if every source is cached.
+    /// - `None` if any source has not been imported yet.
+    pub fn cached(&self, sources: &[Source]) -> Option<Import> {
+        let mut import = Import::default();
+        for source in sources {
+            let entry = self.entries.iter().find(|e| &e.source == source)?;
+            import.append(entry.import.clone());
+        }
+        Some(import.merge())
+    }
+
+    /// Imports the sources whose files changed since they were cached, and updates the index.
+    ///
+    /// Sources that are no longer configured are removed from the index. A source that fails
+    /// to import is cached with its error, and retried once its files change. If its files
+    /// cannot even be determined, for example because the browser is not installed, it is
+    /// retried once they can. A changed source is only imported again once [`RELOAD_DELAY`] has
+    /// passed since its last import, or [`HISTORY_REFRESH_INTERVAL`] if only its history changed.
+    ///
+    /// # Arguments
+    /// - `sources`: The configured sources.
+    ///
+    /// # Returns
+    /// - The merged [`Import`] of all sources, and whether any source was imported again.
+    pub fn refresh(&mut self, sources: &[Source]) -> (Import, bool) {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        self.refresh_at(sources, now)
+    }
+
+    /// Refreshes the index as [`Index::refresh`] does, at the given time in seconds since the
+    /// Unix epoch.
+    fn refresh_at(&mut self, sources: &[Source], now: u64) -> (Import, bool) {
+        let mut entries: Vec<IndexEntry> = Vec::new();
+        let mut import = Import::default();
+        let mut changed = self.entries.len() != sources.len();
+
+        for source in sources {
+            let files: Option<Vec<FileStamp>> = source
+                .files()
+                .ok()
+                .map(|files| files.iter().map(|f| FileStamp::new(f)).collect());
+            let history_files: Vec<FileStamp> = source
+                .history_files()
+                .unwrap_or_default()
+                .iter()
+                .map(|f| FileStamp::new(f))
+                .collect();
+
+            // A source without files can only be cached as failed, as there is nothing to tell
+            // whether it changed.
+            let cached = self.entries.iter().position(|e| {
+                let unchanged = match &files {
+                    Some(files) => &e.files == files,
+                    None => e.failed,
+                } && (e.history_files == history_files
+                    || now < e.imported_at + HISTORY_REFRESH_INTERVAL.as_secs());
+                &e.source == source && (unchanged || now < e.imported_at + RELOAD_DELAY.as_secs())
+            });
+            if let Some(i) = cached {
+                let entry = self.entries.swap_remove(i);
+                import.append(entry.import.clone());
+                entries.push(entry);
+                continue;
+            }
+
+            changed = true;
+            match import_from(source) {
+                Ok(imported) => {
+                    import.append(imported.clone());
+                    entries.push(IndexEntry {
+                        source: source.clone(),
+                        files: files.unwrap_or_default(),
+                        history_files,
+                        imported_at: now,
+                        import: imported,
+                        failed: false,
+                    });
+                }
+                Err(e) => {
+                    let mut failed = Import::default();
+                    failed.errors.push(import_error(source, &e));
+                    import.append(failed.clone());
+                    entries.push(IndexEntry {
+                        source: source.clone(),
+                        files: files.unwrap_or_default(),
+                        history_files,
+                        imported_at: now,
+                        import: failed,
+                        failed: true,
+                    });
+                }
+            }
+        }
+
+        self.entries = entries;
+        (import.merge(), changed)
+    }
+}
+
+/// Watches the configured sources for changes on a background thread.
+///
+/// The index is refreshed right away, and again every [`WATCH_INTERVAL`], see
+/// [`Index::refresh`]. Only checking the files of a source is cheap, so sources are only
+/// imported again after a browser has written to them. The thread stops once the returned
+/// [`Receiver`] is dropped.
+///
+/// # Arguments
+/// - `index`: The index to refresh, saved again whenever a source changed.
+/// - `sources`: The configured sources.
+///
+/// # Returns
+/// - A [`Receiver`] that gets a new [`Import`] whenever a source changed.
+pub fn spawn_watcher(mut index: Index, sources: Vec<Source>) -> Receiver<Import> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || loop {
+        let (mut import, changed) = index.refresh(&sources);
+        if changed {
+            if let Err(e) = index.save_default() {
+                import
+                    .errors
+                    .push(format!("Failed to save bookmark index: {}", e));
+            }
+            if sender.send(import).is_err() {
+                break;
+            }
+        }
+        thread::sleep(WATCH_INTERVAL);
+    });
+    receiver
+}
+
+/// Returns the path of the index in hhand's cache directory.
+fn default_path() -> Result<PathBuf> {
+    let cache_dir =
+        dirs::cache_dir().ok_or_else(|| anyhow::anyhow!("Could not determine cache directory."))?;
+    Ok(cache_dir.join("hhand").join(INDEX_FILE))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bookmarks::importer::Browser;
+
+    #[test]
+    fn test_refresh_reimports_changed_sources() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bookmarks.html");
+        fs::write(
+            &path,
+            r#"<DL><p><DT><A HREF="https://docs.rs">Docs</A></DL>"#,
+        )
+        .unwrap();
+        let mut source = Source::new(Browser::Html);
+        source.path = Some(path.clone());
+        let sources = vec![source];
+
+        let mut index = Index::default();
+        assert!(index.cached(&sources).is_none());
+        let (import, changed) = index.refresh_at(&sources, 1000);
+        assert!(changed);
+        assert_eq!(import.bookmarks.len(), 1);
+
+        let (_, changed) = index.refresh_at(&sources, 1100);
+        assert!(!changed);
+
+        fs::write(
+            &path,
+            r#"<DL><p><DT><A HREF="https://docs.rs">Docs</A><DT><A HREF="https://crates.io">Crates</A></DL>"#,
+        )
+        .unwrap();
+        let (import, changed) = index.refresh_at(&sources, 1200);
+        assert!(changed);
+        assert_eq!(import.bookmarks.len(), 2);
+
+        // Changes right after an import wait for the reload delay.
+        fs::write(
+            &path,
+            r#"<DL><p><DT><A HREF="https://docs.rs">Docs</A></DL>"#,
+        )
+        .unwrap();
+        let (import, changed) = index.refresh_at(&sources, 1210);
+        assert!(!changed);
+        assert_eq!(import.bookmarks.len(), 2);
+        let (import, changed) = index.refresh_at(&sources, 1200 + RELOAD_DELAY.as_secs());
+        assert!(changed);
+        assert_eq!(import.bookmarks.len(), 1);
+
+        let index_path = dir.path().join(INDEX_FILE);
+        index.save(&index_path).unwrap();
+        let cached = Index::load(&index_path).unwrap().cached(&sources).unwrap();
+        assert_eq!(cached.bookmarks.len(), 1);
+    }
+
+    #[test]
+    fn test_refresh_waits_for_history_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bookmarks.html");
+        fs::write(
+            &path,
+            r#"<DL><p><DT><A HREF="https://docs.rs">Docs</A></DL>"#,
+        )
+        .unwrap();
+        let mut source = Source::new(Browser::Html);
+        source.path = Some(path.clone());
+        let sources = vec![source];
+
+        let mut index = Index::default();
+        let (_, changed) = index.refresh_at(&sources, 1000);
+        assert!(changed);
+
+        // Pretend that the history was stamped differently at the last import.
+        index.entries[0].history_files = vec![FileStamp::new(&dir.path().join("History"))];
+        let (_, changed) = index.refresh_at(&sources, 1060);
+        assert!(!changed);
+        let (_, changed) = index.refresh_at(&sources, 1000 + HISTORY_REFRESH_INTERVAL.as_secs());
+        assert!(changed);
+        assert!(index.entries[0].history_files.is_empty());
+    }
+
+    #[test]
+    fn test_refresh_caches_failed_sources() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("missing.html");
+        let mut source = Source::new(Browser::Html);
+        source.path = Some(path.clone());
+        let sources = vec![source];
+
+        let mut index = Index::default();
+        let (import, changed) = index.refresh_at(&sources, 1000);
+        assert!(changed);
+        assert_eq!(import.errors.len(), 1);
+
+        let (import, changed) = index.refresh_at(&sources, 1100);
+        assert!(!changed);
+        assert_eq!(import.errors.len(), 1);
+
+        fs::write(
+            &path,
+            r#"<DL><p><DT><A HREF="https://docs.rs">Docs</A></DL>"#,
+        )
+        .unwrap();
+        let (import, changed) = index.refresh_at(&sources, 1200);
+        assert!(changed);
+        assert!(import.errors.is_empty());
+        assert_eq!(import.bookmarks.len(), 1);
+    }
+}
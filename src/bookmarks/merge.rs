@@ -0,0 +1,211 @@
+//! Bookmark Merging
+//!
+//! This module merges bookmarks and history imported from several sources into one list each.
+//! Entries pointing to the same page are detected by comparing their normalized URLs, and are
+//! combined into a single entry that records every source it was found in.
+
+use std::{cmp::Reverse, collections::HashMap};
+
+use super::{importer::Origin, Bookmark, HistoryEntry};
+
+/// Merges bookmarks with the same normalized URL.
+///
+/// The first occurrence of a URL is kept, in the order of the input, and the origins and tags of
+/// any later duplicates are added to it. Visit counts are added up across sources, and the highest
+/// frecency and most recent use are kept.
+///
+/// Duplicates from the same source share that source's history, so only the highest visit count
+/// among them is counted, rather than counting the same visits once per copy.
+///
+/// # Arguments
+/// - `bookmarks`: The bookmarks to merge.
+///
+/// # Returns
+/// - `Vec<Bookmark>` with one bookmark per normalized URL.
+pub fn merge(bookmarks: Vec<Bookmark>) -> Vec<Bookmark> {
+    let mut merged: Vec<Bookmark> = Vec::new();
+    let mut index_by_url: HashMap<String, usize> = HashMap::new();
+    // Highest visit count per set of origins, for each merged bookmark.
+    let mut visits: Vec<Vec<(Vec<Origin>, u32)>> = Vec::new();
+
+    for bookmark in bookmarks {
+        let key = normalize_url(&bookmark.url);
+        match index_by_url.get(&key) {
+            Some(&i) => {
+                match visits[i].iter_mut().find(|(o, _)| *o == bookmark.origins) {
+                    Some((_, count)) => *count = (*count).max(bookmark.visit_count),
+                    None => visits[i].push((bookmark.origins.clone(), bookmark.visit_count)),
+                }
+                let existing = &mut merged[i];
+                for origin in bookmark.origins {
+                    if !existing.origins.contains(&origin) {
+                        existing.origins.push(origin);
+                    }
+                }
+                for tag in bookmark.tags {
+                    if !existing.tags.contains(&tag) {
+                        existing.tags.push(tag);
+                    }
+                }
+                if existing.keyword.is_none() {
+                    existing.keyword = bookmark.keyword;
+                }
+                if existing.description.is_none() {
+                    existing.description = bookmark.description;
+                }
+                existing.frecency = existing.frecency.max(bookmark.frecency);
+                existing.last_used = existing.last_used.max(bookmark.last_used);
+            }
+            None => {
+                index_by_url.insert(key, merged.len());
+                visits.push(vec![(bookmark.origins.clone(), bookmark.visit_count)]);
+                merged.push(bookmark);
+            }
+        }
+    }
+
+    for (bookmark, visits) in merged.iter_mut().zip(visits) {
+        bookmark.visit_count = visits.iter().map(|(_, count)| count).sum();
+    }
+    merged
+}
+
+/// Merges history entries with the same normalized URL.
+///
+/// Visit counts of duplicates are added up and the most recent visit is kept. The result is
+/// sorted by most recent visit first.
+///
+/// # Arguments
+/// - `entries`: The history entries to merge.
+///
+/// # Returns
+/// - `Vec<HistoryEntry>` with one entry per normalized URL.
+pub fn merge_history(entries: Vec<HistoryEntry>) -> Vec<HistoryEntry> {
+    let mut merged: Vec<HistoryEntry> = Vec::new();
+    let mut index_by_url: HashMap<String, usize> = HashMap::new();
+
+    for entry in entries {
+        let key = normalize_url(&entry.url);
+        match index_by_url.get(&key) {
+            Some(&i) => {
+                let existing = &mut merged[i];
+                existing.visit_count += entry.visit_count;
+                if entry.last_visit > existing.last_visit {
+                    existing.last_visit = entry.last_visit;
+                    if !entry.title.is_empty() {
+                        existing.title = entry.title;
+                    }
+                }
+                for origin in entry.origins {
+                    if !existing.origins.contains(&origin) {
+                        existing.origins.push(origin);
+                    }
+                }
+            }
+            None => {
+                index_by_url.insert(key, merged.len());
+                merged.push(entry);
+            }
+        }
+    }
+
+    merged.sort_by_key(|e| Reverse(e.last_visit));
+    merged
+}
+
+/// Normalizes a URL so that trivially different spellings of the same address compare equal.
+///
+/// The scheme and host are lowercased, default ports and empty fragments are removed, and a
+/// trailing slash is dropped from the path. URLs without an authority, like `javascript:` or
+/// `mailto:` links, are only trimmed.
+///
+/// # Arguments
+/// - `url`: The URL to normalize.
+///
+/// # Returns
+/// - `String` with the normalized URL.
+pub fn normalize_url(url: &str) -> String {
+    let url = url.trim();
+    let Some((scheme, rest)) = url.split_once("://") else {
+        return url.to_string();
+    };
+    let scheme = scheme.to_lowercase();
+
+    let authority_end = rest.find(['/', '?', '#']).unwrap_or(rest.len());
+    let (authority, rest) = rest.split_at(authority_end);
+    let mut authority = authority.to_lowercase();
+    let default_port = match scheme.as_str() {
+        "http" => Some(":80"),
+        "https" => Some(":443"),
+        _ => None,
+    };
+    if let Some(port) = default_port {
+        if let Some(stripped) = authority.strip_suffix(port) {
+            authority = stripped.to_string();
+        }
+    }
+
+    let rest = rest.strip_suffix('#').unwrap_or(rest);
+    let path_end = rest.find(['?', '#']).unwrap_or(rest.len());
+    let (path, suffix) = rest.split_at(path_end);
+    let path = path.trim_end_matches('/');
+
+    format!("{scheme}://{authority}{path}{suffix}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bookmarks::importer::{Browser, Origin};
+
+    fn bookmark(name: &str, url: &str, browser: Browser) -> Bookmark {
+        let mut bookmark = Bookmark::new(name, url);
+        bookmark.origins.push(Origin::new(browser, None));
+        bookmark
+    }
+
+    #[test]
+    fn test_normalize_url() {
+        assert_eq!(
+            normalize_url("HTTPS://Example.COM:443/Path/"),
+            "https://example.com/Path"
+        );
+        assert_eq!(normalize_url("https://example.com/"), "https://example.com");
+        assert_eq!(
+            normalize_url("https://example.com/a/?q=1#top"),
+            "https://example.com/a?q=1#top"
+        );
+        assert_eq!(normalize_url("javascript:alert(1)"), "javascript:alert(1)");
+    }
+
+    #[test]
+    fn test_merge_records_every_origin() {
+        let merged = merge(vec![
+            bookmark("Rust", "https://www.rust-lang.org/", Browser::Firefox),
+            bookmark("Docs", "https://docs.rs", Browser::Firefox),
+            bookmark("Rust Lang", "https://www.rust-lang.org", Browser::Chrome),
+        ]);
+
+        assert_eq!(merged.len(), 2);
+        assert_eq!(merged[0].name, "Rust");
+        assert_eq!(merged[0].origins_label(), "Firefox, Chrome");
+    }
+
+    #[test]
+    fn test_merge_counts_visits_once_per_origin() {
+        let mut copies = Vec::new();
+        for (browser, visit_count) in [
+            (Browser::Chrome, 5),
+            (Browser::Chrome, 5),
+            (Browser::Firefox, 3),
+        ] {
+            let mut copy = bookmark("Rust", "https://www.rust-lang.org", browser);
+            copy.visit_count = visit_count;
+            copies.push(copy);
+        }
+
+        let merged = merge(copies);
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged[0].visit_count, 8);
+    }
+}
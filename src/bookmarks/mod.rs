@@ -1,3 +1,18 @@
+mod date;
+mod exporter;
 mod importer;
+mod index;
+mod merge;
+mod report;
+mod search;
+mod store;
 
-pub use importer::{import_from, Bookmark, Browser};
+pub(crate) use date::{from_iso8601, to_iso8601};
+pub use exporter::{export_to, format_link, ExportFormat, LinkFormat};
+pub(crate) use importer::Profile;
+pub use importer::{import_all, import_unmerged, Bookmark, Browser, HistoryEntry, Import, Source};
+pub use index::{spawn_watcher, Index};
+pub use merge::merge;
+pub use report::Report;
+pub use search::{keyword_search, web_search, SearchEngine, DEFAULT_WEB_SEARCH};
+pub use store::Store;
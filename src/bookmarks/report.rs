@@ -0,0 +1,315 @@
+//! Bookmark Report
+//!
+//! This module checks a bookmark collection for entries worth cleaning up:
+//! - Duplicates: bookmarks whose URLs only differ in ways that do not change the page, like
+//!   `http` vs `https`, a trailing slash or `utm_` tracking parameters.
+//! - Broken local links: `file://` bookmarks whose file no longer exists.
+//! - Bookmarklets: `javascript:` bookmarks, which only run inside a browser.
+//! - Name clashes: bookmarks with the same name pointing to different pages.
+//!
+//! The same bookmark imported from several browsers is not reported as a duplicate, as long as
+//! every browser has it only once under the same URL.
+
+use std::{
+    collections::{HashMap, HashSet},
+    io::Write,
+    path::PathBuf,
+};
+
+use anyhow::Result;
+
+use super::{merge::normalize_url, Bookmark};
+
+/// Problems found in a bookmark collection, see [`Report::build`].
+#[derive(Debug, Default)]
+pub struct Report {
+    /// Groups of bookmarks pointing to the same page, in order of first appearance.
+    pub duplicates: Vec<Vec<Bookmark>>,
+    /// `file://` bookmarks whose file does not exist.
+    pub broken: Vec<Bookmark>,
+    /// `javascript:` bookmarks.
+    pub bookmarklets: Vec<Bookmark>,
+    /// Groups of bookmarks with the same name but different pages.
+    pub name_clashes: Vec<Vec<Bookmark>>,
+}
+
+impl Report {
+    /// Checks the given bookmarks for duplicates, broken local links, bookmarklets and name
+    /// clashes.
+    ///
+    /// # Arguments
+    /// - `bookmarks`: The bookmarks to check, before merging, so that duplicates within a
+    ///   single source are found as well.
+    ///
+    /// # Returns
+    /// - The [`Report`] listing every problem found.
+    pub fn build(bookmarks: &[Bookmark]) -> Report {
+        let mut report = Report::default();
+
+        for group in group_by(bookmarks, |b| duplicate_key(&b.url)) {
+            let urls: HashSet<String> = group.iter().map(|b| normalize_url(&b.url)).collect();
+            let origins = group.iter().flat_map(|b| &b.origins).count();
+            let distinct_origins = group
+                .iter()
+                .flat_map(|b| &b.origins)
+                .map(ToString::to_string)
+                .collect::<HashSet<_>>()
+                .len();
+            if urls.len() > 1 || origins > distinct_origins {
+                report.duplicates.push(group);
+            }
+        }
+
+        for group in group_by(bookmarks, |b| b.name.trim().to_lowercase()) {
+            let pages: HashSet<String> = group.iter().map(|b| duplicate_key(&b.url)).collect();
+            if pages.len() > 1 {
+                report.name_clashes.push(group);
+            }
+        }
+
+        for bookmark in bookmarks {
+            if is_bookmarklet(&bookmark.url) {
+                report.bookmarklets.push(bookmark.clone());
+            } else if file_path(&bookmark.url).is_some_and(|path| !path.exists()) {
+                report.broken.push(bookmark.clone());
+            }
+        }
+
+        report
+    }
+
+    /// Returns whether no problems were found.
+    pub fn is_empty(&self) -> bool {
+        self.duplicates.is_empty()
+            && self.broken.is_empty()
+            && self.bookmarklets.is_empty()
+            && self.name_clashes.is_empty()
+    }
+
+    /// Writes the report as plain text, one section per kind of problem.
+    ///
+    /// # Arguments
+    /// - `writer`: Where to write the report to.
+    ///
+    /// # Returns
+    /// - `Ok(())` on success.
+    /// - `Err(anyhow::Error)` if writing fails.
+    pub fn write_to(&self, writer: &mut impl Write) -> Result<()> {
+        if self.is_empty() {
+            writeln!(writer, "No problems found.")?;
+            return Ok(());
+        }
+
+        writeln!(writer, "Duplicates ({} groups)", self.duplicates.len())?;
+        for group in &self.duplicates {
+            writeln!(writer, "  {}", duplicate_key(&group[0].url))?;
+            write_bookmarks(group, "    ", writer)?;
+        }
+
+        writeln!(writer, "\nBroken local links ({})", self.broken.len())?;
+        write_bookmarks(&self.broken, "  ", writer)?;
+
+        writeln!(writer, "\nBookmarklets ({})", self.bookmarklets.len())?;
+        write_bookmarks(&self.bookmarklets, "  ", writer)?;
+
+        writeln!(
+            writer,
+            "\nSame name, different URLs ({} groups)",
+            self.name_clashes.len()
+        )?;
+        for group in &self.name_clashes {
+            writeln!(writer, "  {}", group[0].name.trim())?;
+            write_bookmarks(group, "    ", writer)?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes one line per bookmark, with its URL, name, folder and sources.
+fn write_bookmarks(bookmarks: &[Bookmark], indent: &str, writer: &mut impl Write) -> Result<()> {
+    for bookmark in bookmarks {
+        write!(writer, "{indent}- {} {:?}", bookmark.url, bookmark.name)?;
+        if !bookmark.folder.is_empty() {
+            write!(writer, " in {}", bookmark.folder)?;
+        }
+        if !bookmark.origins.is_empty() {
+            write!(writer, " [{}]", bookmark.origins_label())?;
+        }
+        writeln!(writer)?;
+    }
+    Ok(())
+}
+
+/// Groups bookmarks by key, keeping groups with more than one bookmark in order of first
+/// appearance.
+fn group_by(bookmarks: &[Bookmark], key: impl Fn(&Bookmark) -> String) -> Vec<Vec<Bookmark>> {
+    let mut groups: Vec<Vec<Bookmark>> = Vec::new();
+    let mut index_by_key: HashMap<String, usize> = HashMap::new();
+
+    for bookmark in bookmarks {
+        let key = key(bookmark);
+        if key.is_empty() {
+            continue;
+        }
+        match index_by_key.get(&key) {
+            Some(&i) => groups[i].push(bookmark.clone()),
+            None => {
+                index_by_key.insert(key, groups.len());
+                groups.push(vec![bookmark.clone()]);
+            }
+        }
+    }
+
+    groups.retain(|group| group.len() > 1);
+    groups
+}
+
+/// Normalizes a URL more aggressively than [`normalize_url`], so that addresses of the same page
+/// compare equal: `http` is treated as `https` and `utm_` query parameters are removed.
+///
+/// # Arguments
+/// - `url`: The URL to normalize.
+///
+/// # Returns
+/// - `String` with the normalized URL.
+fn duplicate_key(url: &str) -> String {
+    let url = normalize_url(url);
+    let url = match url.strip_prefix("http://") {
+        Some(rest) => format!("https://{rest}"),
+        None => url,
+    };
+
+    let (url, fragment) = match url.split_once('#') {
+        Some((url, fragment)) => (url, Some(fragment)),
+        None => (url.as_str(), None),
+    };
+    let (base, query) = url.split_once('?').unwrap_or((url, ""));
+    let query: Vec<&str> = query
+        .split('&')
+        .filter(|param| !param.is_empty() && !param.to_lowercase().starts_with("utm_"))
+        .collect();
+
+    let mut key = base.trim_end_matches('/').to_string();
+    if !query.is_empty() {
+        key.push('?');
+        key.push_str(&query.join("&"));
+    }
+    if let Some(fragment) = fragment {
+        key.push('#');
+        key.push_str(fragment);
+    }
+    key
+}
+
+/// Returns whether a URL is a `javascript:` bookmarklet.
+fn is_bookmarklet(url: &str) -> bool {
+    url.trim()
+        .get(..11)
+        .is_some_and(|scheme| scheme.eq_ignore_ascii_case("javascript:"))
+}
+
+/// Returns the local path of a `file://` URL, with percent-encoded characters decoded.
+///
+/// # Returns
+/// - `Some(PathBuf)` if the URL is a `file://` URL.
+/// - `None` otherwise.
+fn file_path(url: &str) -> Option<PathBuf> {
+    let url = url.trim();
+    let rest = url
+        .get(..7)
+        .filter(|scheme| scheme.eq_ignore_ascii_case("file://"))
+        .map(|_| &url[7..])?;
+    // Skip the host, which is empty or `localhost` for local files.
+    let path = &rest[rest.find('/').unwrap_or(rest.len())..];
+    let path = path.split(['?', '#']).next().unwrap_or_default();
+    let path = percent_decode(path);
+
+    // Windows paths look like `/C:/Users/...`.
+    let is_windows_drive = path.as_bytes().get(2) == Some(&b':');
+    Some(PathBuf::from(if is_windows_drive {
+        &path[1..]
+    } else {
+        &path
+    }))
+}
+
+/// Decodes `%XX` escapes, leaving malformed escapes as they are.
+fn percent_decode(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut decoded: Vec<u8> = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = (bytes[i] == b'%')
+            .then(|| input.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bookmarks::importer::{Browser, Origin};
+
+    fn bookmark(name: &str, url: &str, browser: Browser) -> Bookmark {
+        let mut bookmark = Bookmark::new(name, url);
+        bookmark.origins.push(Origin::new(browser, None));
+        bookmark
+    }
+
+    #[test]
+    fn test_build_report() {
+        let dir = tempfile::tempdir().unwrap();
+        let existing = dir.path().join("notes file.txt");
+        std::fs::write(&existing, "").unwrap();
+        let existing_url = format!("file://{}", existing.to_string_lossy().replace(' ', "%20"));
+        let missing_url = format!("file://{}/gone.txt", dir.path().to_string_lossy());
+
+        let report = Report::build(&[
+            bookmark("Rust", "https://www.rust-lang.org", Browser::Firefox),
+            bookmark("Rust", "https://www.rust-lang.org/", Browser::Chrome),
+            bookmark("Docs", "http://docs.rs/?utm_source=feed", Browser::Firefox),
+            bookmark("Docs", "https://docs.rs", Browser::Firefox),
+            bookmark("Home", "https://example.com", Browser::Firefox),
+            bookmark("home", "https://example.org", Browser::Chrome),
+            bookmark("Notes", &existing_url, Browser::Firefox),
+            bookmark("Old notes", &missing_url, Browser::Firefox),
+            bookmark("Share", "JavaScript:void(0)", Browser::Chrome),
+        ]);
+
+        // The same bookmark in Firefox and Chrome is no duplicate.
+        assert_eq!(report.duplicates.len(), 1);
+        assert_eq!(report.duplicates[0].len(), 2);
+        assert_eq!(report.duplicates[0][0].name, "Docs");
+
+        assert_eq!(report.name_clashes.len(), 1);
+        assert_eq!(report.name_clashes[0][0].url, "https://example.com");
+
+        assert_eq!(report.broken.len(), 1);
+        assert_eq!(report.broken[0].name, "Old notes");
+        assert_eq!(report.bookmarklets.len(), 1);
+    }
+
+    #[test]
+    fn test_duplicate_key() {
+        assert_eq!(
+            duplicate_key("HTTP://Example.com/a/?utm_source=x&id=1&UTM_medium=y#top"),
+            "https://example.com/a?id=1#top"
+        );
+        assert_eq!(
+            duplicate_key("https://example.com/?utm_source=x"),
+            "https://example.com"
+        );
+    }
+}
@@ -0,0 +1,161 @@
+//! Search Engines
+//!
+//! This module turns queries into searches. A search engine is a URL template with a `%s`
+//! placeholder for the search terms and a keyword: typing the keyword followed by search terms,
+//! like `gh tokio`, fills the terms into the template.
+//!
+//! Search engines are defined in the config, and imported from keyword bookmarks with a `%s` in
+//! their URL, as Firefox and Falkon use them, and from the search engines of Chromium-based
+//! browsers. The searches are offered as synthetic bookmarks, so they are listed and opened like
+//! any other bookmark.
+
+use serde::{Deserialize, Serialize};
+
+use super::importer::{Bookmark, Origin};
+
+/// Folder of the synthetic bookmarks created for searches.
+const SEARCH_FOLDER: &str = "Search";
+
+/// Template used for web searches when the config sets none.
+pub const DEFAULT_WEB_SEARCH: &str = "https://duckduckgo.com/?q=%s";
+
+/// A URL template opened by typing its keyword followed by search terms.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct SearchEngine {
+    pub name: String,
+    pub keyword: String,
+    /// URL with `%s` where the percent-encoded search terms go. `%S` inserts them unencoded.
+    pub url: String,
+    /// Where the search engine was imported from. Empty for search engines from the config.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub origins: Vec<Origin>,
+}
+
+impl SearchEngine {
+    /// Creates a new [`SearchEngine`] without origins.
+    pub fn new(name: &str, keyword: &str, url: &str) -> SearchEngine {
+        SearchEngine {
+            name: name.to_string(),
+            keyword: keyword.to_string(),
+            url: url.to_string(),
+            origins: Vec::new(),
+        }
+    }
+
+    /// Returns whether a URL is a search template, with a `%s` or `%S` placeholder.
+    pub fn is_template(url: &str) -> bool {
+        url.contains("%s") || url.contains("%S")
+    }
+
+    /// Returns the URL that searches for the given terms.
+    pub fn url_for(&self, terms: &str) -> String {
+        self.url
+            .replace("%s", &encode_terms(terms))
+            .replace("%S", terms)
+    }
+
+    /// Returns a synthetic bookmark that searches for the given terms.
+    pub fn to_bookmark(&self, terms: &str) -> Bookmark {
+        let mut bookmark =
+            Bookmark::new(&format!("{}: {}", self.name, terms), &self.url_for(terms));
+        bookmark.folder = SEARCH_FOLDER.to_string();
+        bookmark.keyword = Some(self.keyword.clone());
+        bookmark.origins = self.origins.clone();
+        bookmark
+    }
+}
+
+/// Returns the search for a query that starts with the keyword of a search engine.
+///
+/// # Arguments
+/// - `engines`: The search engines, the first one with a matching keyword is used.
+/// - `query`: The query, like `gh tokio`.
+///
+/// # Returns
+/// - `Some(Bookmark)` with the search, if the first word of the query is a keyword and search
+///   terms follow it.
+/// - `None` otherwise.
+pub fn keyword_search(engines: &[SearchEngine], query: &str) -> Option<Bookmark> {
+    let (keyword, terms) = query.trim_start().split_once(char::is_whitespace)?;
+    let terms = terms.trim();
+    if terms.is_empty() {
+        return None;
+    }
+    engines
+        .iter()
+        .find(|e| e.keyword.eq_ignore_ascii_case(keyword))
+        .map(|e| e.to_bookmark(terms))
+}
+
+/// Returns a synthetic bookmark that searches the web for the whole query.
+///
+/// # Arguments
+/// - `template`: URL template of the web search, with a `%s` placeholder.
+/// - `query`: The search terms.
+pub fn web_search(template: &str, query: &str) -> Bookmark {
+    let mut bookmark = SearchEngine::new("Search the web", "", template).to_bookmark(query.trim());
+    bookmark.keyword = None;
+    bookmark
+}
+
+/// Merges search engines with the same keyword, keeping the first one and adding the origins of
+/// the others to it.
+pub fn merge_search_engines(engines: Vec<SearchEngine>) -> Vec<SearchEngine> {
+    let mut merged: Vec<SearchEngine> = Vec::new();
+    for engine in engines {
+        match merged
+            .iter_mut()
+            .find(|e| e.keyword.eq_ignore_ascii_case(&engine.keyword))
+        {
+            Some(existing) => {
+                for origin in engine.origins {
+                    if !existing.origins.contains(&origin) {
+                        existing.origins.push(origin);
+                    }
+                }
+            }
+            None => merged.push(engine),
+        }
+    }
+    merged
+}
+
+/// Percent-encodes search terms for use in a URL, keeping only unreserved characters as is.
+fn encode_terms(terms: &str) -> String {
+    let mut encoded = String::with_capacity(terms.len());
+    for byte in terms.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keyword_search() {
+        let engines = vec![
+            SearchEngine::new("GitHub", "gh", "https://github.com/search?q=%s"),
+            SearchEngine::new("Wiki", "w", "https://en.wikipedia.org/wiki/%S"),
+        ];
+
+        let search = keyword_search(&engines, "GH tokio runtime&co").unwrap();
+        assert_eq!(search.name, "GitHub: tokio runtime&co");
+        assert_eq!(
+            search.url,
+            "https://github.com/search?q=tokio%20runtime%26co"
+        );
+        assert_eq!(search.folder, "Search");
+
+        let search = keyword_search(&engines, "w Rust").unwrap();
+        assert_eq!(search.url, "https://en.wikipedia.org/wiki/Rust");
+
+        assert!(keyword_search(&engines, "gh ").is_none());
+        assert!(keyword_search(&engines, "tokio runtime").is_none());
+    }
+}
@@ -0,0 +1,204 @@
+//! Bookmark Store
+//!
+//! This module provides hhand's own bookmark store, a SQLite database in the user's data
+//! directory (e.g. `~/.local/share/hhand/bookmarks.db` on Linux). Unlike imported bookmarks,
+//! which are read-only snapshots of browser data, bookmarks in the store can be added, edited
+//! and deleted from hhand. They are identified by their URL, which is unique within the store.
+//!
+//! # Errors
+//! Returns an error if the data directory cannot be determined, or if the database cannot be
+//! opened, read or written.
+
+use std::{fs, path::Path};
+
+use anyhow::{bail, Context, Result};
+use rusqlite::{params, Connection};
+
+use super::importer::{Bookmark, Browser, Origin};
+
+/// File name of the store database inside hhand's data directory.
+const STORE_FILE: &str = "bookmarks.db";
+
+/// hhand's own bookmark store.
+pub struct Store {
+    conn: Connection,
+}
+
+impl Store {
+    /// Opens the store in hhand's data directory, creating it if needed.
+    ///
+    /// # Returns
+    /// - `Ok(Store)` on success.
+    /// - `Err(anyhow::Error)` if the data directory cannot be determined or the database cannot
+    ///   be opened.
+    pub fn open_default() -> Result<Store> {
+        let data_dir = dirs::data_dir()
+            .ok_or_else(|| anyhow::anyhow!("Could not determine data directory."))?;
+        Store::open(&data_dir.join("hhand").join(STORE_FILE))
+    }
+
+    /// Opens the store at the given path, creating the database and its directory if needed.
+    ///
+    /// # Arguments
+    /// - `path`: Path of the database file.
+    ///
+    /// # Returns
+    /// - `Ok(Store)` on success.
+    /// - `Err(anyhow::Error)` if the database cannot be created or opened.
+    pub fn open(path: &Path) -> Result<Store> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).with_context(|| format!("Failed to create {:?}", dir))?;
+        }
+        let conn = Connection::open(path).with_context(|| format!("Failed to open {:?}", path))?;
+        Store::with_connection(conn)
+    }
+
+    /// Creates the schema on an open connection, if it does not exist yet.
+    fn with_connection(conn: Connection) -> Result<Store> {
+        conn.execute_batch(
+            "
+            CREATE TABLE IF NOT EXISTS bookmarks (
+                id INTEGER PRIMARY KEY,
+                name TEXT NOT NULL,
+                url TEXT NOT NULL UNIQUE,
+                folder TEXT NOT NULL DEFAULT '',
+                date_added INTEGER
+            );
+            ",
+        )?;
+        Ok(Store { conn })
+    }
+
+    /// Returns all bookmarks in the store, in the order they were added.
+    ///
+    /// # Returns
+    /// - `Ok(Vec<Bookmark>)` with all stored bookmarks.
+    /// - `Err(anyhow::Error)` if the database cannot be queried.
+    pub fn list(&self) -> Result<Vec<Bookmark>> {
+        let mut statement = self
+            .conn
+            .prepare("SELECT name, url, folder, date_added FROM bookmarks ORDER BY id")?;
+        let rows = statement.query_map([], |row| {
+            let mut bookmark = Bookmark::new(
+                row.get::<_, String>(0)?.as_str(),
+                row.get::<_, String>(1)?.as_str(),
+            );
+            bookmark.folder = row.get(2)?;
+            bookmark.date_added = row.get(3)?;
+            bookmark.origins = vec![Origin::new(Browser::Hhand, None)];
+            Ok(bookmark)
+        })?;
+
+        Ok(rows.collect::<rusqlite::Result<Vec<Bookmark>>>()?)
+    }
+
+    /// Adds a bookmark to the store.
+    ///
+    /// # Returns
+    /// - `Ok(())` on success.
+    /// - `Err(anyhow::Error)` if a bookmark with the same URL is already stored, or the database
+    ///   cannot be written.
+    pub fn add(&self, bookmark: &Bookmark) -> Result<()> {
+        if self.contains(&bookmark.url)? {
+            bail!("A bookmark for {} already exists", bookmark.url);
+        }
+        self.conn.execute(
+            "INSERT INTO bookmarks (name, url, folder, date_added) VALUES (?1, ?2, ?3, ?4)",
+            params![
+                bookmark.name,
+                bookmark.url,
+                bookmark.folder,
+                bookmark.date_added
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Replaces the name, URL and folder of the stored bookmark with the given URL. The date it
+    /// was added is kept.
+    ///
+    /// # Arguments
+    /// - `url`: Current URL of the stored bookmark.
+    /// - `bookmark`: The new values.
+    ///
+    /// # Returns
+    /// - `Ok(())` on success.
+    /// - `Err(anyhow::Error)` if no bookmark is stored for `url`, the new URL belongs to another
+    ///   stored bookmark, or the database cannot be written.
+    pub fn update(&self, url: &str, bookmark: &Bookmark) -> Result<()> {
+        if bookmark.url != url && self.contains(&bookmark.url)? {
+            bail!("A bookmark for {} already exists", bookmark.url);
+        }
+        let updated = self.conn.execute(
+            "UPDATE bookmarks SET name = ?1, url = ?2, folder = ?3 WHERE url = ?4",
+            params![bookmark.name, bookmark.url, bookmark.folder, url],
+        )?;
+        if updated == 0 {
+            bail!("No bookmark for {} is stored", url);
+        }
+        Ok(())
+    }
+
+    /// Deletes the stored bookmark with the given URL.
+    ///
+    /// # Returns
+    /// - `Ok(())` on success.
+    /// - `Err(anyhow::Error)` if no bookmark is stored for `url`, or the database cannot be
+    ///   written.
+    pub fn delete(&self, url: &str) -> Result<()> {
+        let deleted = self
+            .conn
+            .execute("DELETE FROM bookmarks WHERE url = ?1", [url])?;
+        if deleted == 0 {
+            bail!("No bookmark for {} is stored", url);
+        }
+        Ok(())
+    }
+
+    /// Returns true if a bookmark with the given URL is stored.
+    fn contains(&self, url: &str) -> Result<bool> {
+        let count: i64 = self.conn.query_row(
+            "SELECT COUNT(*) FROM bookmarks WHERE url = ?1",
+            [url],
+            |row| row.get(0),
+        )?;
+        Ok(count > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_update_and_delete() {
+        let store = Store::with_connection(Connection::open_in_memory().unwrap()).unwrap();
+
+        let mut rust = Bookmark::new("Rust", "https://www.rust-lang.org");
+        rust.folder = "Languages".to_string();
+        rust.date_added = Some(1_698_753_600);
+        store.add(&rust).unwrap();
+        store
+            .add(&Bookmark::new("Docs", "https://docs.rs"))
+            .unwrap();
+        assert!(store.add(&rust).is_err());
+
+        let mut renamed = rust.clone();
+        renamed.name = "Rust Lang".to_string();
+        renamed.url = "https://rust-lang.org".to_string();
+        renamed.date_added = None;
+        store.update(&rust.url, &renamed).unwrap();
+        assert!(store.update("https://docs.rs", &renamed).is_err());
+
+        store.delete("https://docs.rs").unwrap();
+        assert!(store.delete("https://docs.rs").is_err());
+
+        let bookmarks = store.list().unwrap();
+        assert_eq!(bookmarks.len(), 1);
+        assert_eq!(bookmarks[0].name, "Rust Lang");
+        assert_eq!(bookmarks[0].url, "https://rust-lang.org");
+        assert_eq!(bookmarks[0].folder, "Languages");
+        assert_eq!(bookmarks[0].date_added, Some(1_698_753_600));
+        assert_eq!(bookmarks[0].origins_label(), "Hhand");
+    }
+}
@@ -0,0 +1,114 @@
+//! Command Line Interface
+//!
+//! Parses the command line arguments of hhand. Without arguments the interactive terminal UI is
+//! started; subcommands run headless tasks instead.
+//!
+//! # Usage
+//! ```text
+//! hhand                                   Start the terminal UI
+//! hhand export [--format <format>] <file> Export all imported bookmarks to a file
+//! hhand report                            List duplicate and broken bookmarks
+//! ```
+//!
+//! Passing `-` as the file of `export` writes the bookmarks to stdout instead.
+
+use std::path::PathBuf;
+
+use anyhow::Result;
+
+use crate::bookmarks::ExportFormat;
+
+/// Usage text printed when the arguments cannot be parsed.
+pub const USAGE: &str = "Usage:
+    hhand                                   Start the terminal UI
+    hhand export [--format <format>] <file> Export all imported bookmarks to a file
+    hhand report                            List duplicate and broken bookmarks
+
+Export formats: html (default), xbel, json, csv, markdown
+Use - as <file> to write the export to stdout.";
+
+/// The task selected on the command line.
+#[derive(PartialEq, Debug)]
+pub enum Command {
+    /// Start the interactive terminal UI.
+    Run,
+    /// Export all imported bookmarks to a file.
+    Export { format: ExportFormat, path: PathBuf },
+    /// List duplicate, broken and ambiguous bookmarks.
+    Report,
+}
+
+/// Parses the command line arguments, excluding the program name.
+///
+/// # Returns
+/// - `Ok(Command)` with the selected task.
+/// - `Err(anyhow::Error)` if the arguments are invalid.
+pub fn parse_args(args: &[String]) -> Result<Command> {
+    let Some((command, rest)) = args.split_first() else {
+        return Ok(Command::Run);
+    };
+
+    match command.as_str() {
+        "export" => parse_export_args(rest),
+        "report" => match rest.first() {
+            Some(arg) => Err(anyhow::anyhow!("Unexpected argument {:?}", arg)),
+            None => Ok(Command::Report),
+        },
+        _ => Err(anyhow::anyhow!("Unknown command {:?}", command)),
+    }
+}
+
+/// Parses the arguments of the `export` subcommand.
+fn parse_export_args(args: &[String]) -> Result<Command> {
+    let mut format = ExportFormat::Html;
+    let mut path: Option<PathBuf> = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" | "-f" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| anyhow::anyhow!("Missing value for {}", arg))?;
+                format = value.parse()?;
+            }
+            _ if path.is_none() => path = Some(PathBuf::from(arg)),
+            _ => return Err(anyhow::anyhow!("Unexpected argument {:?}", arg)),
+        }
+    }
+
+    let path = path.ok_or_else(|| anyhow::anyhow!("Missing output file for export"))?;
+    Ok(Command::Export { format, path })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_args() {
+        assert_eq!(parse_args(&args(&[])).unwrap(), Command::Run);
+        assert_eq!(
+            parse_args(&args(&["export", "--format", "html", "out.html"])).unwrap(),
+            Command::Export {
+                format: ExportFormat::Html,
+                path: PathBuf::from("out.html")
+            }
+        );
+        assert_eq!(
+            parse_args(&args(&["export", "-f", "json", "-"])).unwrap(),
+            Command::Export {
+                format: ExportFormat::Json,
+                path: PathBuf::from("-")
+            }
+        );
+        assert!(parse_args(&args(&["export"])).is_err());
+        assert_eq!(parse_args(&args(&["report"])).unwrap(), Command::Report);
+        assert!(parse_args(&args(&["report", "extra"])).is_err());
+        assert!(parse_args(&args(&["frobnicate"])).is_err());
+    }
+}
@@ -6,13 +6,35 @@ use std::{
     path::Path,
 };
 
-use crate::bookmarks::Browser;
+use crate::{
+    bookmarks::{Browser, SearchEngine, Source},
+    launcher::Route,
+};
 
 const CONFIG_PATH: &str = "config.toml";
 
 #[derive(Serialize, Deserialize)]
 pub struct Config {
-    pub browser: Browser,
+    /// Sources to import bookmarks from, merged into one list.
+    #[serde(default)]
+    pub sources: Vec<Source>,
+    /// Rules for opening URLs in a given browser and profile, tried in order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub routes: Vec<Route>,
+    /// Search engines, opened by typing their keyword followed by search terms.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub search_engines: Vec<SearchEngine>,
+    /// URL template with a `%s` placeholder, offered as web search for queries without matches.
+    /// Uses DuckDuckGo if unset, and an empty value disables the web search.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub web_search: Option<String>,
+    /// Key that opens the action menu of the selected item, like `Tab` or `Ctrl+K`. Defaults to
+    /// Tab.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub action_key: Option<String>,
+    /// Single browser setting of older config files, migrated to `sources` when loading.
+    #[serde(default, rename = "browser", skip_serializing)]
+    legacy_browser: Option<Browser>,
 }
 
 pub fn load() -> anyhow::Result<Config> {
@@ -30,7 +52,17 @@ fn load_or_default(path: &Path) -> anyhow::Result<Config> {
 
 fn load_from_path(path: &Path) -> anyhow::Result<Config> {
     let content = fs::read_to_string(path)?;
-    let config = toml::from_str(content.as_str())?;
+    let mut config: Config = toml::from_str(content.as_str())?;
+
+    if let Some(browser) = config.legacy_browser.take() {
+        if config.sources.is_empty() {
+            config.sources.push(Source::new(browser));
+        }
+    }
+
+    if config.sources.is_empty() {
+        config.sources = Config::default().sources;
+    }
     Ok(config)
 }
 
@@ -55,7 +87,12 @@ fn save_to_path(config: &Config, path: &Path) -> anyhow::Result<()> {
 impl Default for Config {
     fn default() -> Self {
         Config {
-            browser: Browser::Firefox,
+            sources: vec![Source::new(Browser::Firefox)],
+            routes: Vec::new(),
+            search_engines: Vec::new(),
+            web_search: None,
+            action_key: None,
+            legacy_browser: None,
         }
     }
 }
@@ -64,27 +101,30 @@ impl Default for Config {
 mod tests {
     use super::*;
     use std::fs;
-    use std::path::Path;
-
-    const TEST_CONFIG_PATH: &str = "test_config.toml";
-
-    fn cleanup_test_config() {
-        let _ = fs::remove_file(TEST_CONFIG_PATH);
-    }
 
     #[test]
     fn test_save_to_path() {
-        cleanup_test_config();
-        let path = Path::new(TEST_CONFIG_PATH);
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(CONFIG_PATH);
         let config = Config::default();
 
-        let save_res = save_to_path(&config, path);
+        let save_res = save_to_path(&config, &path);
         assert!(save_res.is_ok());
 
-        let load_res = load_from_path(path);
+        let load_res = load_from_path(&path);
         assert!(load_res.is_ok());
 
         let loaded_config = load_res.unwrap();
-        assert_eq!(&config.browser, &loaded_config.browser);
+        assert_eq!(&config.sources, &loaded_config.sources);
+    }
+
+    #[test]
+    fn test_load_legacy_browser() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(CONFIG_PATH);
+        fs::write(&path, "browser = \"Chrome\"\n").unwrap();
+
+        let loaded_config = load_from_path(&path).unwrap();
+        assert_eq!(loaded_config.sources, vec![Source::new(Browser::Chrome)]);
     }
 }
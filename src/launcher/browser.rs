@@ -0,0 +1,343 @@
+use anyhow::anyhow;
+
+use super::{locator::spawn, LaunchableApp};
+use crate::bookmarks::{Bookmark, Browser, Profile};
+
+/// Names identifying each browser: executables and Flatpak IDs in desktop entries on Linux, and
+/// Start Menu shortcut names on Windows. Compared case-insensitively.
+const BROWSER_NAMES: &[(Browser, &[&str])] = &[
+    (
+        Browser::Chrome,
+        &[
+            "google-chrome",
+            "google-chrome-stable",
+            "com.google.Chrome",
+            "Google Chrome",
+        ],
+    ),
+    (
+        Browser::Chromium,
+        &["chromium", "chromium-browser", "org.chromium.Chromium"],
+    ),
+    (
+        Browser::Brave,
+        &["brave", "brave-browser", "com.brave.Browser", "Brave"],
+    ),
+    (
+        Browser::Edge,
+        &[
+            "microsoft-edge",
+            "microsoft-edge-stable",
+            "com.microsoft.Edge",
+            "Microsoft Edge",
+        ],
+    ),
+    (
+        Browser::Vivaldi,
+        &["vivaldi", "vivaldi-stable", "com.vivaldi.Vivaldi"],
+    ),
+    (
+        Browser::Opera,
+        &["opera", "com.opera.Opera", "Opera Browser"],
+    ),
+    (
+        Browser::Firefox,
+        &["firefox", "org.mozilla.firefox", "Mozilla Firefox"],
+    ),
+    (
+        Browser::LibreWolf,
+        &["librewolf", "io.gitlab.librewolf-community"],
+    ),
+    (Browser::Waterfox, &["waterfox", "net.waterfox.waterfox"]),
+    (Browser::Floorp, &["floorp", "one.ablaze.floorp"]),
+    (
+        Browser::Zen,
+        &["zen", "zen-browser", "app.zen_browser.zen", "Zen Browser"],
+    ),
+    (
+        Browser::Qutebrowser,
+        &["qutebrowser", "org.qutebrowser.qutebrowser"],
+    ),
+    (Browser::GnomeWeb, &["epiphany", "org.gnome.Epiphany"]),
+    (Browser::Falkon, &["falkon", "org.kde.falkon"]),
+    (Browser::Konqueror, &["konqueror", "org.kde.konqueror"]),
+];
+
+/// How a browser should open a page.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum WindowMode {
+    /// Let the browser decide, usually a new tab in an existing window.
+    Default,
+    NewWindow,
+    /// A private or incognito window.
+    Private,
+    /// A window without browser controls, as supported by Chromium-based browsers.
+    App,
+}
+
+/// A browser found among the installed apps, with its profiles.
+#[derive(Debug, Clone)]
+pub struct InstalledBrowser {
+    pub browser: Browser,
+    pub app: LaunchableApp,
+    /// Profiles of the browser, with the default profile first.
+    pub profiles: Vec<Profile>,
+}
+
+/// Finds the browsers among the installed apps, keeping the first app found for each browser.
+pub fn locate_browsers(apps: &[LaunchableApp]) -> Vec<InstalledBrowser> {
+    let mut browsers: Vec<InstalledBrowser> = Vec::new();
+    for app in apps {
+        let Some(browser) = identify(app) else {
+            continue;
+        };
+        if browsers.iter().any(|b| b.browser == browser) {
+            continue;
+        }
+        browsers.push(InstalledBrowser {
+            browser,
+            app: app.clone(),
+            profiles: browser.list_profiles().unwrap_or_default(),
+        });
+    }
+    browsers
+}
+
+/// Returns the browser an app launches, identified by its executable, Flatpak ID or name.
+fn identify(app: &LaunchableApp) -> Option<Browser> {
+    let mut words = app
+        .exec_handle
+        .split_whitespace()
+        .filter(|w| !w.starts_with('-'));
+    let executable = words
+        .next()
+        .map(|w| w.trim_matches('"').rsplit(['/', '\\']).next().unwrap_or(w))
+        .unwrap_or_default();
+    let is_flatpak = executable == "flatpak";
+
+    BROWSER_NAMES.iter().find_map(|(browser, names)| {
+        let matches = names.iter().any(|name| {
+            name.eq_ignore_ascii_case(executable)
+                || name.eq_ignore_ascii_case(&app.name)
+                || (is_flatpak && app.exec_handle.split_whitespace().any(|w| w == *name))
+        });
+        matches.then_some(*browser)
+    })
+}
+
+impl InstalledBrowser {
+    /// Returns the profile matching the profile name of an imported bookmark.
+    pub fn find_profile(&self, name: &str) -> Option<&Profile> {
+        self.profiles.iter().find(|p| p.matches(name))
+    }
+
+    /// Returns the installed browser and profile that a bookmark was imported from.
+    ///
+    /// # Returns
+    /// - `Some((browser, profile))` for the first origin of the bookmark that is installed.
+    /// - `None` if the bookmark was not imported from an installed browser.
+    pub fn for_bookmark<'a>(
+        browsers: &'a [InstalledBrowser],
+        bookmark: &Bookmark,
+    ) -> Option<(&'a InstalledBrowser, Option<&'a Profile>)> {
+        bookmark.origins.iter().find_map(|origin| {
+            let browser = browsers.iter().find(|b| b.browser == origin.browser)?;
+            let profile = origin
+                .profile
+                .as_deref()
+                .and_then(|p| browser.find_profile(p));
+            Some((browser, profile))
+        })
+    }
+
+    /// Opens a URL in the browser.
+    ///
+    /// # Arguments
+    /// - `url`: The URL to open.
+    /// - `profile`: The profile to open it in, or `None` for the profile the browser picks.
+    /// - `mode`: The kind of window to open it in.
+    /// - `flags`: Extra command line flags, passed before the URL.
+    ///
+    /// # Returns
+    /// - `Ok(())` if the browser was started.
+    /// - `Err(anyhow::Error)` if the browser does not support the mode or cannot be started.
+    pub fn open(
+        &self,
+        url: &str,
+        profile: Option<&Profile>,
+        mode: WindowMode,
+        flags: &[String],
+    ) -> anyhow::Result<()> {
+        spawn(&self.command_line(url, profile, mode, flags)?)
+    }
+
+    /// Returns the full command line that [`InstalledBrowser::open`] runs.
+    pub fn command_line(
+        &self,
+        url: &str,
+        profile: Option<&Profile>,
+        mode: WindowMode,
+        flags: &[String],
+    ) -> anyhow::Result<Vec<String>> {
+        let mut args = self.arguments(url, profile, mode)?;
+        // The URL, or the flag holding it, comes last.
+        let url_arg = args.pop();
+        args.extend(flags.iter().cloned());
+        args.extend(url_arg);
+        self.app.command_line(&args)
+    }
+
+    /// Returns the arguments that open a URL in the given profile and kind of window.
+    fn arguments(
+        &self,
+        url: &str,
+        profile: Option<&Profile>,
+        mode: WindowMode,
+    ) -> anyhow::Result<Vec<String>> {
+        let unsupported = || anyhow!("{:?} cannot open {:?} windows", self.browser, mode);
+        let mut args: Vec<String> = Vec::new();
+
+        match self.browser {
+            Browser::Chrome
+            | Browser::Chromium
+            | Browser::Brave
+            | Browser::Edge
+            | Browser::Vivaldi
+            | Browser::Opera => {
+                if let Some(profile) = profile.filter(|p| !p.dir_name.is_empty()) {
+                    args.push(format!("--profile-directory={}", profile.dir_name));
+                }
+                match mode {
+                    WindowMode::Default => {}
+                    WindowMode::NewWindow => args.push("--new-window".to_string()),
+                    WindowMode::Private => args.push(
+                        match self.browser {
+                            Browser::Edge => "--inprivate",
+                            Browser::Opera => "--private",
+                            _ => "--incognito",
+                        }
+                        .to_string(),
+                    ),
+                    // The URL is part of the flag, so return early.
+                    WindowMode::App => {
+                        args.push(format!("--app={url}"));
+                        return Ok(args);
+                    }
+                }
+            }
+            Browser::Firefox
+            | Browser::LibreWolf
+            | Browser::Waterfox
+            | Browser::Floorp
+            | Browser::Zen => {
+                if let Some(profile) = profile {
+                    args.extend(["-P".to_string(), profile.name.clone()]);
+                }
+                match mode {
+                    WindowMode::Default => {}
+                    WindowMode::NewWindow => args.push("--new-window".to_string()),
+                    WindowMode::Private => args.push("--private-window".to_string()),
+                    WindowMode::App => return Err(unsupported()),
+                }
+            }
+            Browser::Qutebrowser => match mode {
+                WindowMode::Default => {}
+                WindowMode::NewWindow => args.extend(["--target", "window"].map(String::from)),
+                WindowMode::Private => {
+                    args.extend(["--target", "private-window"].map(String::from))
+                }
+                WindowMode::App => return Err(unsupported()),
+            },
+            Browser::GnomeWeb => match mode {
+                WindowMode::Default => {}
+                WindowMode::NewWindow => args.push("--new-window".to_string()),
+                WindowMode::Private => args.push("--private-instance".to_string()),
+                WindowMode::App => return Err(unsupported()),
+            },
+            Browser::Falkon => {
+                if let Some(profile) = profile {
+                    args.push(format!("--profile={}", profile.dir_name));
+                }
+                match mode {
+                    WindowMode::Default => {}
+                    WindowMode::NewWindow => args.push("--new-window".to_string()),
+                    WindowMode::Private => args.push("--private-browsing".to_string()),
+                    WindowMode::App => return Err(unsupported()),
+                }
+            }
+            // Konqueror opens every URL passed on the command line in a new window.
+            Browser::Konqueror => match mode {
+                WindowMode::Default | WindowMode::NewWindow => {}
+                WindowMode::Private | WindowMode::App => return Err(unsupported()),
+            },
+            Browser::Html | Browser::Xbel | Browser::Buku | Browser::Hhand => {
+                return Err(anyhow!("{:?} is not a browser", self.browser))
+            }
+        }
+
+        args.push(url.to_string());
+        Ok(args)
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn profile(dir_name: &str, name: &str) -> Profile {
+        Profile {
+            dir_name: dir_name.to_string(),
+            name: name.to_string(),
+            path: PathBuf::from("/tmp").join(dir_name),
+        }
+    }
+
+    #[test]
+    fn test_locate_and_build_command_lines() {
+        let apps = vec![
+            LaunchableApp::new("Files", "nautilus --new-window %U"),
+            LaunchableApp::new("Google Chrome", "/usr/bin/google-chrome-stable %U"),
+            LaunchableApp::new(
+                "Firefox",
+                "/usr/bin/flatpak run --branch=stable --command=firefox org.mozilla.firefox %u",
+            ),
+        ];
+        let mut browsers = locate_browsers(&apps);
+        assert_eq!(browsers.len(), 2);
+        assert_eq!(browsers[0].browser, Browser::Chrome);
+        assert_eq!(browsers[1].browser, Browser::Firefox);
+
+        let chrome = &mut browsers[0];
+        chrome.profiles = vec![profile("Default", "Personal"), profile("Profile 1", "Work")];
+        let work = chrome.find_profile("Work");
+        assert_eq!(
+            chrome
+                .command_line("https://docs.rs", work, WindowMode::Private, &[])
+                .unwrap(),
+            vec![
+                "/usr/bin/google-chrome-stable",
+                "--profile-directory=Profile 1",
+                "--incognito",
+                "https://docs.rs",
+            ]
+        );
+        assert_eq!(
+            chrome
+                .command_line("https://docs.rs", None, WindowMode::App, &[])
+                .unwrap(),
+            vec!["/usr/bin/google-chrome-stable", "--app=https://docs.rs"]
+        );
+
+        let firefox = &browsers[1];
+        assert_eq!(
+            firefox
+                .command_line("https://docs.rs", None, WindowMode::NewWindow, &[])
+                .unwrap()[4..],
+            ["org.mozilla.firefox", "--new-window", "https://docs.rs"]
+        );
+        assert!(firefox
+            .command_line("https://docs.rs", None, WindowMode::App, &[])
+            .is_err());
+    }
+}
@@ -1,5 +1,9 @@
 use anyhow::anyhow;
-use std::process::{Command, Stdio};
+use std::{
+    env,
+    path::PathBuf,
+    process::{Command, Stdio},
+};
 
 use super::{locator_linux, locator_win};
 
@@ -7,6 +11,8 @@ use super::{locator_linux, locator_win};
 pub struct LaunchableApp {
     pub name: String,
     pub exec_handle: String,
+    /// Desktop entry or shortcut the app was located from.
+    pub path: Option<PathBuf>,
 }
 
 pub fn locate_apps() -> anyhow::Result<Vec<LaunchableApp>> {
@@ -27,44 +33,106 @@ impl LaunchableApp {
         LaunchableApp {
             name: name.to_string(),
             exec_handle: exec_handle.to_string(),
+            path: None,
         }
     }
 
+    /// Sets the desktop entry or shortcut the app was located from.
+    pub fn with_path(mut self, path: PathBuf) -> Self {
+        self.path = Some(path);
+        self
+    }
+
     pub fn launch(&self) -> anyhow::Result<()> {
+        spawn(&self.command_line(&[])?)
+    }
+
+    /// Launches the app inside a terminal, so that its output stays visible.
+    pub fn launch_in_terminal(&self) -> anyhow::Result<()> {
+        spawn(&self.terminal_command_line()?)
+    }
+
+    /// Returns the command line that launches the app inside a terminal: the one in
+    /// `$TERMINAL`, or `x-terminal-emulator` on Linux and a console window on Windows.
+    pub fn terminal_command_line(&self) -> anyhow::Result<Vec<String>> {
         if cfg!(target_os = "windows") {
-            self.launch_windows()?;
-        } else if cfg!(target_os = "linux") {
-            self.launch_linux()?
-        } else {
-            return Err(anyhow!("Unsupported OS for launching applications"));
+            return Ok([
+                "cmd",
+                "/C",
+                "start",
+                "",
+                "cmd",
+                "/K",
+                self.exec_handle.as_str(),
+            ]
+            .map(str::to_string)
+            .to_vec());
         }
-        Ok(())
+        let terminal = env::var("TERMINAL")
+            .ok()
+            .filter(|t| !t.trim().is_empty())
+            .unwrap_or_else(|| "x-terminal-emulator".to_string());
+        let mut command_line: Vec<String> =
+            terminal.split_whitespace().map(str::to_string).collect();
+        command_line.push("-e".to_string());
+        command_line.extend(self.command_line(&[])?);
+        Ok(command_line)
     }
 
-    fn launch_windows(&self) -> anyhow::Result<()> {
-        std::process::Command::new("cmd")
-            .args(["/C", "start", "", self.exec_handle.as_str()])
-            .spawn()?;
-
-        Ok(())
+    /// Shows the desktop entry or shortcut of the app in the file manager.
+    pub fn reveal(&self) -> anyhow::Result<()> {
+        let path = self
+            .path
+            .as_ref()
+            .ok_or_else(|| anyhow!("{} was not located from a file", self.name))?;
+        if cfg!(target_os = "windows") {
+            return spawn(&[
+                "explorer".to_string(),
+                format!("/select,{}", path.display()),
+            ]);
+        }
+        // File managers have no common way to select a file, so its folder is opened instead.
+        let folder = path.parent().unwrap_or(path);
+        Ok(open::that(folder)?)
     }
 
-    fn launch_linux(&self) -> anyhow::Result<()> {
-        let cleaned = self
+    /// Returns the command line that launches the app with the given extra arguments, program
+    /// first.
+    ///
+    /// The command line is run without a shell, except that a Windows shortcut without
+    /// arguments is started through `cmd /C start`. Arguments, which may hold untrusted URLs,
+    /// are passed straight to the program the shortcut points to.
+    pub fn command_line(&self, args: &[String]) -> anyhow::Result<Vec<String>> {
+        if cfg!(target_os = "windows") {
+            return locator_win::command_line(&self.exec_handle, args);
+        } else if !cfg!(target_os = "linux") {
+            return Err(anyhow!("Unsupported OS for launching applications"));
+        }
+        // Drop the field codes of the desktop entry, like `%U`.
+        let mut command_line: Vec<String> = self
             .exec_handle
             .split_whitespace()
             .filter(|part| !part.starts_with('%'))
-            .collect::<Vec<_>>();
+            .map(str::to_string)
+            .collect();
+        command_line.extend(args.iter().cloned());
+        Ok(command_line)
+    }
+}
 
-        if !cleaned.is_empty() {
-            Command::new(cleaned[0])
-                .args(&cleaned[1..])
-                .stdout(Stdio::null()) // Discard stdout
-                .stderr(Stdio::null()) // Discard stderr
-                .spawn()?;
-        }
-        Ok(())
+/// Starts a program in the background, discarding its output.
+///
+/// # Arguments
+/// - `command_line`: The program followed by its arguments. Nothing is started if it is empty.
+pub fn spawn(command_line: &[String]) -> anyhow::Result<()> {
+    if let Some((program, args)) = command_line.split_first() {
+        Command::new(program)
+            .args(args)
+            .stdout(Stdio::null()) // Discard stdout
+            .stderr(Stdio::null()) // Discard stderr
+            .spawn()?;
     }
+    Ok(())
 }
 
 #[test]
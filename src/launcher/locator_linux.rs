@@ -52,7 +52,9 @@ fn parse_ini_file(file_path: &Path) -> anyhow::Result<Option<LaunchableApp>> {
     let ini_file = Ini::load_from_file(file_path)?;
     if let Some(sec) = ini_file.section(Some("Desktop Entry")) {
         if let (Some(name), Some(exec)) = (sec.get("Name"), sec.get("Exec")) {
-            return Ok(Some(LaunchableApp::new(name, exec)));
+            return Ok(Some(
+                LaunchableApp::new(name, exec).with_path(file_path.to_path_buf()),
+            ));
         }
     }
     Ok(None)
@@ -1,4 +1,8 @@
-use std::{fs::read_dir, path::Path};
+use anyhow::anyhow;
+use std::{
+    fs::{self, read_dir},
+    path::{Path, PathBuf},
+};
 
 use super::LaunchableApp;
 
@@ -41,7 +45,7 @@ fn get_apps(path: &Path) -> anyhow::Result<Vec<LaunchableApp>> {
                 .unwrap_or_default();
 
             let path = file_path.to_str().unwrap_or_default();
-            apps.push(LaunchableApp::new(name, path));
+            apps.push(LaunchableApp::new(name, path).with_path(file_path.clone()));
         } else if file_path.is_dir() {
             apps.extend(get_apps(&file_path)?);
         }
@@ -49,3 +53,117 @@ fn get_apps(path: &Path) -> anyhow::Result<Vec<LaunchableApp>> {
 
     Ok(apps)
 }
+
+/// Returns the command line that starts a Start Menu shortcut with the given arguments.
+///
+/// A shortcut without arguments is started through `cmd /C start`, which resolves it like the
+/// Start Menu does. `cmd` would interpret characters like `&` in arguments as shell syntax, so
+/// with arguments the program the shortcut points to is started directly instead.
+///
+/// # Returns
+/// - `Ok(Vec<String>)` with the program and its arguments.
+/// - `Err(anyhow::Error)` if arguments are given and the shortcut has no local target.
+pub fn command_line(shortcut: &str, args: &[String]) -> anyhow::Result<Vec<String>> {
+    if args.is_empty() {
+        return Ok(["cmd", "/C", "start", "", shortcut]
+            .map(str::to_string)
+            .to_vec());
+    }
+    let target = fs::read(shortcut)
+        .ok()
+        .and_then(|bytes| shortcut_target(&bytes))
+        .ok_or_else(|| anyhow!("Cannot find the program that {} starts", shortcut))?;
+    let mut command_line = vec![target.to_string_lossy().into_owned()];
+    command_line.extend(args.iter().cloned());
+    Ok(command_line)
+}
+
+/// Reads the local path a shell link (`.lnk`) points to, as described in [MS-SHLLINK].
+///
+/// [MS-SHLLINK]: https://learn.microsoft.com/openspecs/windows_protocols/ms-shllink
+fn shortcut_target(bytes: &[u8]) -> Option<PathBuf> {
+    const HEADER_SIZE: usize = 0x4C;
+    const HAS_LINK_TARGET_ID_LIST: u32 = 0x1;
+    const HAS_LINK_INFO: u32 = 0x2;
+    const VOLUME_ID_AND_LOCAL_BASE_PATH: u32 = 0x1;
+
+    let u16_at = |offset: usize| -> Option<usize> {
+        Some(u16::from_le_bytes(bytes.get(offset..offset + 2)?.try_into().ok()?) as usize)
+    };
+    let u32_at = |offset: usize| -> Option<usize> {
+        Some(u32::from_le_bytes(bytes.get(offset..offset + 4)?.try_into().ok()?) as usize)
+    };
+
+    let flags = u32_at(0x14)? as u32;
+    if u32_at(0)? != HEADER_SIZE || flags & HAS_LINK_INFO == 0 {
+        return None;
+    }
+    let mut link_info = HEADER_SIZE;
+    if flags & HAS_LINK_TARGET_ID_LIST != 0 {
+        link_info += 2 + u16_at(link_info)?;
+    }
+
+    let header_size = u32_at(link_info + 4)?;
+    let link_info_flags = u32_at(link_info + 8)? as u32;
+    if link_info_flags & VOLUME_ID_AND_LOCAL_BASE_PATH == 0 {
+        return None;
+    }
+    // Newer links also store the path as UTF-16, which keeps characters outside the code page.
+    let path = if header_size >= 0x24 {
+        let start = link_info + u32_at(link_info + 28)?;
+        let units: Vec<u16> = bytes
+            .get(start..)?
+            .chunks_exact(2)
+            .map(|c| u16::from_le_bytes([c[0], c[1]]))
+            .take_while(|&u| u != 0)
+            .collect();
+        String::from_utf16(&units).ok()?
+    } else {
+        let start = link_info + u32_at(link_info + 16)?;
+        let path = bytes.get(start..)?.split(|&b| b == 0).next()?;
+        String::from_utf8_lossy(path).into_owned()
+    };
+    (!path.is_empty()).then(|| PathBuf::from(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Builds a shell link with an empty target ID list and an ANSI local base path.
+    fn shell_link(target: &str) -> Vec<u8> {
+        let mut bytes = vec![0u8; 0x4C];
+        bytes[0] = 0x4C;
+        bytes[0x14] = 0x1 | 0x2;
+        bytes.extend(0u16.to_le_bytes());
+
+        let path_offset = 0x1Cu32;
+        let size = path_offset as usize + target.len() + 1;
+        for value in [size as u32, 0x1C, 0x1, 0, path_offset, 0, 0] {
+            bytes.extend(value.to_le_bytes());
+        }
+        bytes.extend(target.as_bytes());
+        bytes.push(0);
+        bytes
+    }
+
+    #[test]
+    fn test_command_line_skips_shell_for_arguments() {
+        let dir = tempfile::tempdir().unwrap();
+        let shortcut = dir.path().join("Google Chrome.lnk");
+        let target = r"C:\Program Files\Google\Chrome\Application\chrome.exe";
+        fs::write(&shortcut, shell_link(target)).unwrap();
+        let shortcut = shortcut.to_str().unwrap();
+
+        let url = "https://example.com/?a=1&b=2|calc".to_string();
+        assert_eq!(
+            command_line(shortcut, std::slice::from_ref(&url)).unwrap(),
+            vec![target.to_string(), url]
+        );
+        assert_eq!(
+            command_line(shortcut, &[]).unwrap()[..3],
+            ["cmd", "/C", "start"]
+        );
+        assert!(command_line("missing.lnk", &["https://x/&calc".to_string()]).is_err());
+    }
+}
@@ -1,5 +1,9 @@
+mod browser;
 mod locator;
 mod locator_linux;
 mod locator_win;
+mod routing;
 
+pub use browser::{locate_browsers, InstalledBrowser, WindowMode};
 pub use locator::{locate_apps, LaunchableApp};
+pub use routing::{Opener, Route};
@@ -0,0 +1,305 @@
+use anyhow::anyhow;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use super::{locator::spawn, InstalledBrowser, WindowMode};
+use crate::bookmarks::{Bookmark, Browser};
+
+/// A rule that opens matching URLs in a given browser and profile.
+///
+/// A route without `host` and `regex` matches every URL, so it can be added last as a catch-all.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct Route {
+    /// Glob matched against the host of the URL, like `*.corp.example.com`. `*` matches any
+    /// number of characters and `?` a single character. Case-insensitive.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>,
+    /// Regular expression matched against the whole URL.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub regex: Option<String>,
+    pub browser: Browser,
+    /// Profile to open the URL in, by display name or directory name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+    /// Extra command line flags for the browser, like `--new-window`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub flags: Vec<String>,
+}
+
+impl Route {
+    /// Returns whether the route applies to a URL.
+    ///
+    /// # Returns
+    /// - `Ok(bool)` with whether both the host glob and the regex match, if set.
+    /// - `Err(anyhow::Error)` if the regex is invalid.
+    pub fn matches(&self, url: &str) -> anyhow::Result<bool> {
+        if let Some(glob) = &self.host {
+            if !glob_matches(&glob.to_lowercase(), &host(url).to_lowercase()) {
+                return Ok(false);
+            }
+        }
+        if let Some(pattern) = &self.regex {
+            let regex = Regex::new(pattern)
+                .map_err(|e| anyhow!("Invalid route regex {:?}: {}", pattern, e))?;
+            if !regex.is_match(url) {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+}
+
+/// Decides how URLs are opened. In order of preference, a URL is opened
+/// - by the first matching [`Route`],
+/// - in the browser and profile a bookmark was imported from, if installed,
+/// - with the command in `$BROWSER`,
+/// - with the system default.
+pub struct Opener {
+    pub browsers: Vec<InstalledBrowser>,
+    routes: Vec<Route>,
+    /// Value of the `$BROWSER` environment variable.
+    fallback: Option<String>,
+}
+
+impl Opener {
+    pub fn new(
+        browsers: Vec<InstalledBrowser>,
+        routes: Vec<Route>,
+        fallback: Option<String>,
+    ) -> Self {
+        Opener {
+            browsers,
+            routes,
+            fallback,
+        }
+    }
+
+    /// Opens a URL, see [`Opener::command_line`] for how it is opened.
+    pub fn open(&self, url: &str, bookmark: Option<&Bookmark>) -> anyhow::Result<()> {
+        match self.command_line(url, bookmark)? {
+            Some(command_line) => spawn(&command_line),
+            None => Ok(open::that(url)?),
+        }
+    }
+
+    /// Returns the command line that opens a URL.
+    ///
+    /// # Arguments
+    /// - `url`: The URL to open.
+    /// - `bookmark`: The bookmark being opened, if any, to open it where it was imported from.
+    ///
+    /// # Returns
+    /// - `Ok(Some(Vec<String>))` with the program and its arguments.
+    /// - `Ok(None)` if the URL should be opened with the system default.
+    /// - `Err(anyhow::Error)` if a matching route is invalid, or names a browser or profile that
+    ///   is not installed.
+    pub fn command_line(
+        &self,
+        url: &str,
+        bookmark: Option<&Bookmark>,
+    ) -> anyhow::Result<Option<Vec<String>>> {
+        for route in &self.routes {
+            if !route.matches(url)? {
+                continue;
+            }
+            let browser = self
+                .browsers
+                .iter()
+                .find(|b| b.browser == route.browser)
+                .ok_or_else(|| anyhow!("{:?} is not installed", route.browser))?;
+            let profile = match &route.profile {
+                Some(name) => Some(browser.find_profile(name).ok_or_else(|| {
+                    anyhow!("Profile {:?} not found for {:?}", name, route.browser)
+                })?),
+                None => None,
+            };
+            return browser
+                .command_line(url, profile, WindowMode::Default, &route.flags)
+                .map(Some);
+        }
+
+        if let Some((browser, profile)) =
+            bookmark.and_then(|b| InstalledBrowser::for_bookmark(&self.browsers, b))
+        {
+            return browser
+                .command_line(url, profile, WindowMode::Default, &[])
+                .map(Some);
+        }
+
+        Ok(self
+            .fallback
+            .as_deref()
+            .and_then(|command| browser_env_command(command, url)))
+    }
+}
+
+/// Builds a command line from the value of `$BROWSER`, a list of commands of which the first is
+/// used. `%s` in the command is replaced by the URL, which is appended otherwise.
+fn browser_env_command(value: &str, url: &str) -> Option<Vec<String>> {
+    // Windows paths contain `:`, so the list is separated by `;` there.
+    let separator = if cfg!(target_os = "windows") {
+        ';'
+    } else {
+        ':'
+    };
+    let command = value
+        .split(separator)
+        .map(str::trim)
+        .find(|c| !c.is_empty())?;
+
+    let mut has_placeholder = false;
+    let mut command_line: Vec<String> = command
+        .split_whitespace()
+        .map(|word| {
+            has_placeholder |= word.contains("%s");
+            word.replace("%s", url)
+        })
+        .collect();
+    if !has_placeholder {
+        command_line.push(url.to_string());
+    }
+    Some(command_line)
+}
+
+/// Returns the host of a URL, without user info and port, or an empty string if it has none.
+fn host(url: &str) -> &str {
+    let Some((_, rest)) = url.split_once("://") else {
+        return "";
+    };
+    let authority = &rest[..rest.find(['/', '?', '#']).unwrap_or(rest.len())];
+    let host = authority.rsplit('@').next().unwrap_or(authority);
+    // Drop the port, but keep IPv6 addresses like `[::1]` intact.
+    match host.rsplit_once(':') {
+        Some((name, port))
+            if port.chars().all(|c| c.is_ascii_digit())
+                && (!host.starts_with('[') || name.ends_with(']')) =>
+        {
+            name
+        }
+        _ => host,
+    }
+}
+
+/// Matches text against a glob where `*` matches any number of characters and `?` a single
+/// character.
+fn glob_matches(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // Position after the last `*` and the text position it was tried at, for backtracking.
+    let mut star: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p + 1, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = star {
+            p = star_p;
+            t = star_t + 1;
+            star = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use crate::{
+        bookmarks::Profile,
+        launcher::{locate_browsers, LaunchableApp},
+    };
+    use std::path::PathBuf;
+
+    fn route(host: Option<&str>, regex: Option<&str>, browser: Browser) -> Route {
+        Route {
+            host: host.map(str::to_string),
+            regex: regex.map(str::to_string),
+            browser,
+            profile: None,
+            flags: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_route_matches() {
+        let corp = route(Some("*.Corp.example.com"), None, Browser::Chrome);
+        assert!(corp
+            .matches("https://wiki.corp.example.com:8443/x")
+            .unwrap());
+        assert!(corp.matches("https://user@git.corp.example.com").unwrap());
+        assert!(!corp.matches("https://corp.example.com.evil.org").unwrap());
+
+        let docs = route(None, Some(r"^https://docs\.rs/"), Browser::Firefox);
+        assert!(docs.matches("https://docs.rs/serde").unwrap());
+        assert!(!docs.matches("https://crates.io").unwrap());
+        assert!(route(None, Some("("), Browser::Firefox)
+            .matches("x")
+            .is_err());
+    }
+
+    #[test]
+    fn test_command_line_order() {
+        let mut browsers = locate_browsers(&[
+            LaunchableApp::new("Google Chrome", "google-chrome %U"),
+            LaunchableApp::new("Firefox", "firefox %u"),
+        ]);
+        browsers[0].profiles = vec![Profile {
+            dir_name: "Profile 1".to_string(),
+            name: "Work".to_string(),
+            path: PathBuf::from("/tmp/Profile 1"),
+        }];
+
+        let mut work = route(Some("*.corp.example.com"), None, Browser::Chrome);
+        work.profile = Some("work".to_string());
+        work.flags = vec!["--new-window".to_string()];
+        let opener = Opener::new(browsers.clone(), vec![work], Some("lynx:w3m".to_string()));
+
+        assert_eq!(
+            opener
+                .command_line("https://wiki.corp.example.com", None)
+                .unwrap()
+                .unwrap(),
+            vec![
+                "google-chrome",
+                "--profile-directory=Profile 1",
+                "--new-window",
+                "https://wiki.corp.example.com",
+            ]
+        );
+
+        let mut bookmark = Bookmark::new("Docs", "https://docs.rs");
+        bookmark.origins = serde_json::from_str(r#"[{"browser": "Firefox"}]"#).unwrap();
+        assert_eq!(
+            opener
+                .command_line("https://docs.rs", Some(&bookmark))
+                .unwrap()
+                .unwrap(),
+            vec!["firefox", "https://docs.rs"]
+        );
+        assert_eq!(
+            opener
+                .command_line("https://docs.rs", None)
+                .unwrap()
+                .unwrap(),
+            vec!["lynx", "https://docs.rs"]
+        );
+
+        let opener = Opener::new(browsers, Vec::new(), None);
+        assert_eq!(opener.command_line("https://docs.rs", None).unwrap(), None);
+    }
+
+    #[test]
+    fn test_browser_env_command() {
+        assert_eq!(
+            browser_env_command("qutebrowser --target tab %s:firefox", "https://docs.rs").unwrap(),
+            vec!["qutebrowser", "--target", "tab", "https://docs.rs"]
+        );
+        assert_eq!(browser_env_command("", "https://docs.rs"), None);
+    }
+}
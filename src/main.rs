@@ -7,29 +7,74 @@ use crossterm::{
 use ratatui::{prelude::CrosstermBackend, Terminal};
 
 use std::{
-    io::{self, stdout},
+    env,
+    fs::File,
+    io::{self, stdout, BufWriter, Write},
+    path::Path,
     process,
 };
 
+use bookmarks::{Bookmark, ExportFormat, Index, Report, Store};
+use cli::Command;
+use config::Config;
+
 mod app;
 mod bookmarks;
+mod cli;
 mod config;
 mod launcher;
 mod ui;
 
 fn main() -> anyhow::Result<()> {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let command = match cli::parse_args(&args) {
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, cli::USAGE);
+            process::exit(2);
+        }
+        Ok(c) => c,
+    };
+
     let config = config::load()?;
-    let bookmarks = match bookmarks::import_from(&config.browser) {
+    match command {
+        Command::Export { format, path } => return export(&config, format, &path),
+        Command::Report => return report(&config),
+        Command::Run => {}
+    }
+
+    // Show the cached bookmarks right away, while the watcher refreshes them in the background.
+    // Without a cache there is nothing to show yet, so the first import has to finish before
+    // drawing.
+    let mut index = Index::load_default();
+    let mut import = match index.cached(&config.sources) {
+        Some(import) => import,
+        None => {
+            let (mut import, _) = index.refresh(&config.sources);
+            if let Err(e) = index.save_default() {
+                import
+                    .errors
+                    .push(format!("Failed to save bookmark index: {}", e));
+            }
+            import
+        }
+    };
+    let updates = bookmarks::spawn_watcher(index, config.sources.clone());
+    let store = match Store::open_default() {
+        Ok(store) => Some(store),
         Err(e) => {
-            cleanup_terminal().ok();
-            eprintln!(
-                "Failed to import bookmarks from {:#?}: {}",
-                &config.browser, e
-            );
-            process::exit(1);
+            import
+                .errors
+                .push(format!("Failed to open bookmark store: {}", e));
+            None
         }
-        Ok(b) => b,
     };
+    if store.is_none() && import.bookmarks.is_empty() && !import.errors.is_empty() {
+        cleanup_terminal().ok();
+        for e in &import.errors {
+            eprintln!("{}", e);
+        }
+        process::exit(1);
+    }
 
     let apps = match launcher::locate_apps() {
         Err(e) => {
@@ -48,11 +93,68 @@ fn main() -> anyhow::Result<()> {
     let backend = CrosstermBackend::new(stdout);
     let terminal = Terminal::new(backend)?;
 
-    let app_result = App::new(bookmarks, apps, config).run(terminal);
+    let app_result = App::new(import, updates, store, apps, config).run(terminal);
     cleanup_terminal()?;
     app_result
 }
 
+/// Imports bookmarks from all configured sources and the bookmark store, and writes them to a
+/// file.
+fn export(config: &Config, format: ExportFormat, path: &Path) -> anyhow::Result<()> {
+    let import = bookmarks::import_all(&config.sources);
+    for e in &import.errors {
+        eprintln!("{}", e);
+    }
+
+    let mut all_bookmarks = stored_bookmarks();
+    all_bookmarks.extend(import.bookmarks);
+    let all_bookmarks = bookmarks::merge(all_bookmarks);
+
+    // A path of `-` writes to stdout, so the export can be piped into other tools.
+    let mut writer: BufWriter<Box<dyn Write>> = if path == Path::new("-") {
+        BufWriter::new(Box::new(stdout()))
+    } else {
+        BufWriter::new(Box::new(File::create(path)?))
+    };
+    bookmarks::export_to(&all_bookmarks, format, &mut writer)?;
+    writer.flush()?;
+
+    if path == Path::new("-") {
+        eprintln!("Exported {} bookmarks", all_bookmarks.len());
+    } else {
+        eprintln!("Exported {} bookmarks to {:?}", all_bookmarks.len(), path);
+    }
+    Ok(())
+}
+
+/// Lists the bookmarks in the bookmark store. A store that cannot be opened or read is treated
+/// as empty with a warning, like a source that fails to import.
+fn stored_bookmarks() -> Vec<Bookmark> {
+    match Store::open_default().and_then(|store| store.list()) {
+        Ok(bookmarks) => bookmarks,
+        Err(e) => {
+            eprintln!("Failed to read bookmark store: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+fn report(config: &Config) -> anyhow::Result<()> {
+    // Duplicates are searched before merging, which would hide those within a single source.
+    let import = bookmarks::import_unmerged(&config.sources);
+    for e in &import.errors {
+        eprintln!("{}", e);
+    }
+
+    let mut all_bookmarks = stored_bookmarks();
+    all_bookmarks.extend(import.bookmarks);
+
+    let mut writer = BufWriter::new(stdout());
+    Report::build(&all_bookmarks).write_to(&mut writer)?;
+    writer.flush()?;
+    Ok(())
+}
+
 // Use this function to cleanup instead of ratatui::restore() as we need to call DisableMouseCapture when running application on Linux.
 fn cleanup_terminal() -> io::Result<()> {
     disable_raw_mode()?;
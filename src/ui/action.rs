@@ -0,0 +1,76 @@
+use crate::bookmarks::{Bookmark, LinkFormat};
+
+/// Something to do with the selected bookmark or app. Keys and the action menu both resolve to
+/// an [`Action`], which the app runs through one dispatch.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Action {
+    Open,
+    OpenWith,
+    CopyUrl,
+    CopyLink(LinkFormat),
+    ShowDetails,
+    ShowQrCode,
+    /// Adds a bookmark to the store, regardless of the selection.
+    Add,
+    Edit,
+    Delete,
+    Launch,
+    LaunchInTerminal,
+    CopyExec,
+    RevealFile,
+}
+
+/// Actions offered in the menu of a bookmark, in order. Editing and deleting are only offered
+/// for bookmarks in the store, see [`Action::is_available_for`].
+pub const BOOKMARK_ACTIONS: [Action; 10] = [
+    Action::Open,
+    Action::CopyUrl,
+    Action::CopyLink(LinkFormat::Markdown),
+    Action::CopyLink(LinkFormat::Org),
+    Action::CopyLink(LinkFormat::Html),
+    Action::OpenWith,
+    Action::ShowDetails,
+    Action::ShowQrCode,
+    Action::Edit,
+    Action::Delete,
+];
+
+/// Actions offered in the menu of an app, in order.
+pub const APP_ACTIONS: [Action; 4] = [
+    Action::Launch,
+    Action::LaunchInTerminal,
+    Action::CopyExec,
+    Action::RevealFile,
+];
+
+impl Action {
+    /// Returns the label of the action in the action menu.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Action::Open => "Open",
+            Action::OpenWith => "Open with…",
+            Action::CopyUrl => "Copy URL",
+            Action::CopyLink(LinkFormat::Markdown) => "Copy as Markdown link",
+            Action::CopyLink(LinkFormat::Org) => "Copy as Org link",
+            Action::CopyLink(LinkFormat::Html) => "Copy as HTML link",
+            Action::ShowDetails => "Show details",
+            Action::ShowQrCode => "Show QR code",
+            Action::Add => "Add bookmark",
+            Action::Edit => "Edit",
+            Action::Delete => "Delete",
+            Action::Launch => "Launch",
+            Action::LaunchInTerminal => "Launch in terminal",
+            Action::CopyExec => "Copy Exec line",
+            Action::RevealFile => "Reveal desktop file",
+        }
+    }
+
+    /// Returns whether the action applies to a bookmark. Only bookmarks added in hhand live in
+    /// the store and can be edited or deleted.
+    pub fn is_available_for(&self, bookmark: &Bookmark) -> bool {
+        match self {
+            Action::Edit | Action::Delete => bookmark.is_stored(),
+            _ => true,
+        }
+    }
+}
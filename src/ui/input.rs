@@ -1,6 +1,12 @@
-use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use anyhow::anyhow;
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use std::{str::FromStr, time::Duration};
 
-use crate::app::AppState;
+use super::Action;
+use crate::{app::AppState, launcher::WindowMode};
+
+/// How long to wait for input before giving control back to the run loop.
+const POLL_TIMEOUT: Duration = Duration::from_millis(250);
 
 pub enum Control {
     ShouldExit,
@@ -8,34 +14,141 @@ pub enum Control {
     _PasteInput,
     Delete,
     SetBookmarksState,
+    SetHistoryState,
     SetLauncherState,
     SelectNextBookmark,
     SelectPreviousBookmark,
+    SelectNextHistoryEntry,
+    SelectPreviousHistoryEntry,
     SelectNextApp,
     SelectPreviousApp,
-    OpenBookmark,
-    LaunchApp,
+    OpenHistoryEntry,
+    Run(Action),
     Clear,
     ConfigVisible(bool),
     None,
     ConfigNext,
     ConfigPrevious,
+    EditorInput(String),
+    EditorDelete,
+    EditorPaste,
+    EditorNextField,
+    EditorPreviousField,
+    SaveBookmark,
+    CloseEditor,
+    ShowActions,
+    ActionsNext,
+    ActionsPrevious,
+    RunSelectedAction,
+    CloseActions,
+    CloseDetails,
+    CloseQrCode,
+    OpenWithNext,
+    OpenWithPrevious,
+    OpenWithSelected(WindowMode),
+    CloseOpenWith,
 }
 
 pub struct InputHandler {
     mode: AppState,
     config_visible: bool,
+    editor_visible: bool,
+    open_with_visible: bool,
+    actions_visible: bool,
+    details_visible: bool,
+    qr_code_visible: bool,
+    /// Key that opens the action menu.
+    action_key: KeyBinding,
+}
+
+/// A key with its modifiers, parsed from names like `Tab`, `F2` or `Ctrl+K`.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct KeyBinding {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl KeyBinding {
+    /// Returns whether a key event is this key. Shift is ignored for characters, as it is
+    /// already part of the character.
+    fn matches(&self, key: &KeyEvent) -> bool {
+        let modifiers = match key.code {
+            KeyCode::Char(_) => key.modifiers.difference(KeyModifiers::SHIFT),
+            _ => key.modifiers,
+        };
+        key.code == self.code && modifiers == self.modifiers
+    }
+}
+
+impl Default for KeyBinding {
+    fn default() -> Self {
+        KeyBinding {
+            code: KeyCode::Tab,
+            modifiers: KeyModifiers::NONE,
+        }
+    }
+}
+
+impl FromStr for KeyBinding {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        let mut parts: Vec<&str> = s.split('+').map(str::trim).collect();
+        let key = parts.pop().unwrap_or_default();
+        let mut modifiers = KeyModifiers::NONE;
+        for modifier in parts {
+            modifiers |= match modifier.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => return Err(anyhow!("Unknown modifier {:?} in key {:?}", modifier, s)),
+            };
+        }
+
+        let code = match key.to_ascii_lowercase().as_str() {
+            "tab" => KeyCode::Tab,
+            "enter" => KeyCode::Enter,
+            "space" => KeyCode::Char(' '),
+            "insert" => KeyCode::Insert,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            name => {
+                let mut chars = name.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => KeyCode::Char(c),
+                    _ => match name.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                        Some(number) => KeyCode::F(number),
+                        None => return Err(anyhow!("Unknown key {:?}", s)),
+                    },
+                }
+            }
+        };
+        Ok(KeyBinding { code, modifiers })
+    }
 }
 
 impl InputHandler {
-    pub fn new() -> Self {
+    pub fn new(action_key: KeyBinding) -> Self {
         InputHandler {
             mode: AppState::Bookmarks,
             config_visible: false,
+            editor_visible: false,
+            open_with_visible: false,
+            actions_visible: false,
+            details_visible: false,
+            qr_code_visible: false,
+            action_key,
         }
     }
 
+    /// Waits up to [`POLL_TIMEOUT`] for an input event and returns its control, so that the
+    /// caller can handle other updates in between.
     pub fn read(&self) -> Control {
+        if !event::poll(POLL_TIMEOUT).unwrap() {
+            return Control::None;
+        }
         let key = match event::read().unwrap() {
             Event::Key(key) => key,
             _ => return Control::None,
@@ -45,6 +158,58 @@ impl InputHandler {
             return Control::None;
         }
 
+        if self.editor_visible {
+            let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+            return match key.code {
+                KeyCode::Esc => Control::CloseEditor,
+                KeyCode::Enter => Control::SaveBookmark,
+                KeyCode::Tab | KeyCode::Down => Control::EditorNextField,
+                KeyCode::BackTab | KeyCode::Up => Control::EditorPreviousField,
+                KeyCode::Backspace => Control::EditorDelete,
+                KeyCode::Char('v') if ctrl => Control::EditorPaste,
+                KeyCode::Char(value) if !ctrl => Control::EditorInput(value.to_string()),
+                _ => Control::None,
+            };
+        }
+
+        if self.open_with_visible {
+            return match key.code {
+                KeyCode::Esc => Control::CloseOpenWith,
+                KeyCode::Down => Control::OpenWithNext,
+                KeyCode::Up => Control::OpenWithPrevious,
+                KeyCode::Enter => Control::OpenWithSelected(WindowMode::Default),
+                KeyCode::Char('w') => Control::OpenWithSelected(WindowMode::NewWindow),
+                KeyCode::Char('p') => Control::OpenWithSelected(WindowMode::Private),
+                KeyCode::Char('a') => Control::OpenWithSelected(WindowMode::App),
+                _ => Control::None,
+            };
+        }
+
+        if self.actions_visible {
+            return match key.code {
+                KeyCode::Esc => Control::CloseActions,
+                KeyCode::Down => Control::ActionsNext,
+                KeyCode::Up => Control::ActionsPrevious,
+                KeyCode::Enter => Control::RunSelectedAction,
+                _ if self.action_key.matches(&key) => Control::CloseActions,
+                _ => Control::None,
+            };
+        }
+
+        if self.details_visible {
+            return match key.code {
+                KeyCode::Esc | KeyCode::Enter => Control::CloseDetails,
+                _ => Control::None,
+            };
+        }
+
+        if self.qr_code_visible {
+            return match key.code {
+                KeyCode::Esc | KeyCode::Enter => Control::CloseQrCode,
+                _ => Control::None,
+            };
+        }
+
         match key.code {
             KeyCode::Esc => Control::ShouldExit,
             _ => {
@@ -62,26 +227,50 @@ impl InputHandler {
                     };
                 }
 
+                if self.action_key.matches(&key) && !matches!(self.mode, AppState::History) {
+                    return Control::ShowActions;
+                }
+
                 match self.mode {
                     AppState::Bookmarks => match key.code {
-                        KeyCode::PageDown => Control::SetLauncherState,
+                        KeyCode::PageDown => Control::SetHistoryState,
                         KeyCode::PageUp => Control::SetLauncherState,
                         KeyCode::Down => Control::SelectNextBookmark,
                         KeyCode::Up => Control::SelectPreviousBookmark,
                         KeyCode::Backspace => Control::Delete,
                         KeyCode::Delete => Control::Clear,
-                        KeyCode::Enter => Control::OpenBookmark,
+                        KeyCode::Enter => Control::Run(Action::Open),
+                        KeyCode::Char(value) if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                            match value {
+                                'n' => Control::Run(Action::Add),
+                                'e' => Control::Run(Action::Edit),
+                                'd' => Control::Run(Action::Delete),
+                                'o' => Control::Run(Action::OpenWith),
+                                _ => Control::None,
+                            }
+                        }
+                        KeyCode::Char(value) => Control::Input(value.to_string()),
+                        _ => Control::None,
+                    },
+                    AppState::History => match key.code {
+                        KeyCode::PageDown => Control::SetLauncherState,
+                        KeyCode::PageUp => Control::SetBookmarksState,
+                        KeyCode::Down => Control::SelectNextHistoryEntry,
+                        KeyCode::Up => Control::SelectPreviousHistoryEntry,
+                        KeyCode::Backspace => Control::Delete,
+                        KeyCode::Delete => Control::Clear,
+                        KeyCode::Enter => Control::OpenHistoryEntry,
                         KeyCode::Char(value) => Control::Input(value.to_string()),
                         _ => Control::None,
                     },
                     AppState::Launcher => match key.code {
                         KeyCode::PageDown => Control::SetBookmarksState,
-                        KeyCode::PageUp => Control::SetBookmarksState,
+                        KeyCode::PageUp => Control::SetHistoryState,
                         KeyCode::Down => Control::SelectNextApp,
                         KeyCode::Up => Control::SelectPreviousApp,
                         KeyCode::Backspace => Control::Delete,
                         KeyCode::Delete => Control::Clear,
-                        KeyCode::Enter => Control::LaunchApp,
+                        KeyCode::Enter => Control::Run(Action::Launch),
                         KeyCode::Char(value) => Control::Input(value.to_string()),
                         _ => Control::None,
                     },
@@ -97,4 +286,40 @@ impl InputHandler {
     pub fn set_config_visible(&mut self, visible: bool) {
         self.config_visible = visible;
     }
+
+    pub fn set_editor_visible(&mut self, visible: bool) {
+        self.editor_visible = visible;
+    }
+
+    pub fn set_open_with_visible(&mut self, visible: bool) {
+        self.open_with_visible = visible;
+    }
+
+    pub fn set_actions_visible(&mut self, visible: bool) {
+        self.actions_visible = visible;
+    }
+
+    pub fn set_details_visible(&mut self, visible: bool) {
+        self.details_visible = visible;
+    }
+
+    pub fn set_qr_code_visible(&mut self, visible: bool) {
+        self.qr_code_visible = visible;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_key_binding() {
+        assert_eq!("Tab".parse::<KeyBinding>().unwrap(), KeyBinding::default());
+        let ctrl_k: KeyBinding = "Ctrl+K".parse().unwrap();
+        assert!(ctrl_k.matches(&KeyEvent::new(KeyCode::Char('k'), KeyModifiers::CONTROL)));
+        assert!(!ctrl_k.matches(&KeyEvent::new(KeyCode::Char('k'), KeyModifiers::NONE)));
+        assert_eq!("f2".parse::<KeyBinding>().unwrap().code, KeyCode::F(2));
+        assert!("Hyper+K".parse::<KeyBinding>().is_err());
+        assert!("Tabulator".parse::<KeyBinding>().is_err());
+    }
 }
@@ -4,11 +4,13 @@ use ratatui::{
     style::{Color, Modifier, Style, Stylize},
     text::{Line, Span},
     widgets::{
-        Block, Borders, HighlightSpacing, List, ListItem, Paragraph, StatefulWidget, Widget,
+        Block, Borders, Clear, HighlightSpacing, List, ListItem, Paragraph, StatefulWidget, Widget,
+        Wrap,
     },
 };
 
-use crate::app::{App, AppState, ConfigElement, StatusMessage};
+use crate::app::{App, AppState, ConfigElement, StatusMessage, EDITOR_FIELDS};
+use crate::bookmarks::to_iso8601;
 
 const COLOR_TITLE_FG: Color = Color::Rgb(139, 233, 253); // Cyan
 const COLOR_FG: Color = Color::Rgb(80, 250, 123); // Green
@@ -42,6 +44,10 @@ impl Widget for &mut App {
                 self.render_bookmarks_header(buf, header_area);
                 self.render_bookmarks_list(buf, main_area)
             }
+            AppState::History => {
+                self.render_history_header(buf, header_area);
+                self.render_history_list(buf, main_area);
+            }
             AppState::Launcher => {
                 self.render_launcher_header(buf, header_area);
                 self.render_apps_list(buf, main_area);
@@ -52,6 +58,26 @@ impl Widget for &mut App {
             self.render_config(buf, main_area);
         }
 
+        if self.editor.is_some() {
+            self.render_editor(buf, main_area);
+        }
+
+        if self.action_menu.is_some() {
+            self.render_action_menu(buf, main_area);
+        }
+
+        if self.details.is_some() {
+            self.render_details(buf, main_area);
+        }
+
+        if self.qr_code.is_some() {
+            self.render_qr_code(buf, main_area);
+        }
+
+        if self.open_with.is_some() {
+            self.render_open_with(buf, main_area);
+        }
+
         self.render_footer(buf, footer_area);
     }
 }
@@ -66,6 +92,14 @@ impl App {
             .render(area, buf);
     }
 
+    fn render_history_header(&self, buf: &mut Buffer, area: Rect) {
+        let block = Block::bordered().title(self.title.as_str());
+        Paragraph::new(self.input_str.clone())
+            .block(block)
+            .style(Style::default().bg(COLOR_BG))
+            .render(area, buf);
+    }
+
     fn render_launcher_header(&self, buf: &mut Buffer, area: Rect) {
         let block = Block::bordered().title(self.title.as_str());
         Paragraph::new(self.input_str.clone())
@@ -80,7 +114,14 @@ impl App {
 
         for m in matches {
             list_items.push(ListItem::new(Line::from(Span::styled(
-                format!("{: <40} : {}", m.name, m.url),
+                format!(
+                    "{: <40} : {: <30} : {: <20} : {}{}",
+                    m.name,
+                    m.folder,
+                    m.origins_label(),
+                    m.url,
+                    m.tags.iter().map(|t| format!(" #{t}")).collect::<String>()
+                ),
                 Style::default().fg(COLOR_FG),
             ))));
         }
@@ -99,6 +140,32 @@ impl App {
         StatefulWidget::render(list, area, buf, &mut self.bookmark_list.state);
     }
 
+    fn render_history_list(&mut self, buf: &mut Buffer, area: Rect) {
+        let mut list_items = Vec::<ListItem>::new();
+        let matches = self.search_history();
+
+        for m in matches {
+            let last_visit = m.last_visit.map(format_timestamp).unwrap_or_default();
+            list_items.push(ListItem::new(Line::from(Span::styled(
+                format!("{: <16} : {: <40} : {}", last_visit, m.title, m.url),
+                Style::default().fg(COLOR_FG),
+            ))));
+        }
+
+        let block = Block::bordered()
+            .title(Line::raw("History ").left_aligned())
+            .border_style(Style::default().fg(COLOR_TITLE_FG).bg(COLOR_BG))
+            .bg(COLOR_BG);
+
+        let list = List::new(list_items)
+            .block(block)
+            .highlight_style(SELECTED_STYLE)
+            .highlight_symbol("> ")
+            .highlight_spacing(HighlightSpacing::Always);
+
+        StatefulWidget::render(list, area, buf, &mut self.history_list.state);
+    }
+
     fn render_apps_list(&mut self, buf: &mut Buffer, area: Rect) {
         let mut list_items = Vec::<ListItem>::new();
         let matches = self.search_apps();
@@ -193,6 +260,188 @@ impl App {
             .render(cols_row_4[1], buf);
     }
 
+    fn render_editor(&mut self, buf: &mut Buffer, area: Rect) {
+        let popup_area = self.centered_rect(60, 50, area);
+        let Some(editor) = &self.editor else {
+            return;
+        };
+
+        let title = if editor.is_adding() {
+            "Add bookmark"
+        } else {
+            "Edit bookmark"
+        };
+        let popup_block = Block::bordered()
+            .title(title)
+            .border_style(Style::default().fg(COLOR_TITLE_FG))
+            .style(Style::default().bg(Color::DarkGray));
+        let inner_area = popup_block.inner(popup_area);
+        Clear.render(popup_area, buf);
+        popup_block.render(popup_area, buf);
+
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(EDITOR_FIELDS.map(|_| Constraint::Length(3)))
+            .split(inner_area);
+        let active_style = Style::default().bg(Color::LightYellow).fg(Color::Black);
+
+        for (i, label) in EDITOR_FIELDS.iter().enumerate() {
+            let mut block = Block::bordered().title(*label);
+            if i == editor.active_field {
+                block = block.style(active_style);
+            }
+            Paragraph::new(editor.fields[i].as_str())
+                .block(block)
+                .render(rows[i], buf);
+        }
+    }
+
+    fn render_open_with(&mut self, buf: &mut Buffer, area: Rect) {
+        let popup_area = self.centered_rect(50, 50, area);
+        let Some(menu) = &mut self.open_with else {
+            return;
+        };
+
+        let list_items: Vec<ListItem> = menu
+            .labels()
+            .into_iter()
+            .map(|label| {
+                ListItem::new(Line::from(Span::styled(
+                    label,
+                    Style::default().fg(COLOR_FG),
+                )))
+            })
+            .collect();
+        let block = Block::bordered()
+            .title("Open with")
+            .border_style(Style::default().fg(COLOR_TITLE_FG))
+            .style(Style::default().bg(Color::DarkGray));
+        let list = List::new(list_items)
+            .block(block)
+            .highlight_style(SELECTED_STYLE)
+            .highlight_symbol("> ")
+            .highlight_spacing(HighlightSpacing::Always);
+
+        Clear.render(popup_area, buf);
+        StatefulWidget::render(list, popup_area, buf, &mut menu.state);
+    }
+
+    fn render_action_menu(&mut self, buf: &mut Buffer, area: Rect) {
+        let popup_area = self.centered_rect(40, 50, area);
+        let Some(menu) = &mut self.action_menu else {
+            return;
+        };
+
+        let list_items: Vec<ListItem> = menu
+            .labels()
+            .into_iter()
+            .map(|label| {
+                ListItem::new(Line::from(Span::styled(
+                    label,
+                    Style::default().fg(COLOR_FG),
+                )))
+            })
+            .collect();
+        let block = Block::bordered()
+            .title(menu.title.as_str())
+            .border_style(Style::default().fg(COLOR_TITLE_FG))
+            .style(Style::default().bg(Color::DarkGray));
+        let list = List::new(list_items)
+            .block(block)
+            .highlight_style(SELECTED_STYLE)
+            .highlight_symbol("> ")
+            .highlight_spacing(HighlightSpacing::Always);
+
+        Clear.render(popup_area, buf);
+        StatefulWidget::render(list, popup_area, buf, &mut menu.state);
+    }
+
+    fn render_details(&mut self, buf: &mut Buffer, area: Rect) {
+        let popup_area = self.centered_rect(70, 60, area);
+        let Some(bookmark) = &self.details else {
+            return;
+        };
+
+        let optional_date = |date: Option<i64>| date.map(format_timestamp).unwrap_or_default();
+        let fields = [
+            ("Name", bookmark.name.clone()),
+            ("URL", bookmark.url.clone()),
+            ("Folder", bookmark.folder.clone()),
+            ("Tags", bookmark.tags.join(", ")),
+            ("Keyword", bookmark.keyword.clone().unwrap_or_default()),
+            (
+                "Description",
+                bookmark.description.clone().unwrap_or_default(),
+            ),
+            ("Added", optional_date(bookmark.date_added)),
+            ("Last used", optional_date(bookmark.last_used)),
+            ("Visits", bookmark.visit_count.to_string()),
+            ("Sources", bookmark.origins_label()),
+        ];
+        let lines: Vec<Line> = fields
+            .into_iter()
+            .map(|(label, value)| {
+                Line::from(vec![
+                    Span::styled(format!("{label: <12}"), Style::default().fg(COLOR_TITLE_FG)),
+                    Span::styled(value, Style::default().fg(COLOR_FG)),
+                ])
+            })
+            .collect();
+
+        let block = Block::bordered()
+            .title("Details")
+            .border_style(Style::default().fg(COLOR_TITLE_FG))
+            .style(Style::default().bg(Color::DarkGray));
+        Clear.render(popup_area, buf);
+        Paragraph::new(lines)
+            .block(block)
+            .wrap(Wrap { trim: false })
+            .render(popup_area, buf);
+    }
+
+    /// Draws the QR code as large as the area allows, in a popup that fits tightly around it.
+    fn render_qr_code(&mut self, buf: &mut Buffer, area: Rect) {
+        let message_area = self.centered_rect(60, 30, area);
+        let Some(view) = &self.qr_code else {
+            return;
+        };
+        let block = Block::bordered()
+            .title(view.url.as_str())
+            .border_style(Style::default().fg(COLOR_TITLE_FG))
+            .style(Style::default().bg(Color::DarkGray));
+
+        // Leave room for the border on each side.
+        let inner_width = area.width.saturating_sub(2) as usize;
+        let inner_height = area.height.saturating_sub(2) as usize;
+        let scale = view.scale_to_fit(inner_width, inner_height);
+        if scale == 0 {
+            let (width, height) = view.size(1);
+            Clear.render(message_area, buf);
+            Paragraph::new(format!(
+                "The QR code needs {}x{} characters, enlarge the terminal to show it",
+                width + 2,
+                height + 2
+            ))
+            .block(block)
+            .wrap(Wrap { trim: true })
+            .render(message_area, buf);
+            return;
+        }
+
+        let (width, height) = view.size(scale);
+        let (width, height) = (width as u16 + 2, height as u16 + 2);
+        let popup_area = Rect::new(
+            area.x + (area.width - width) / 2,
+            area.y + (area.height - height) / 2,
+            width,
+            height,
+        );
+        Clear.render(popup_area, buf);
+        Paragraph::new(view.lines(scale))
+            .block(block)
+            .render(popup_area, buf);
+    }
+
     fn centered_rect(&mut self, percent_x: u16, percent_y: u16, r: Rect) -> Rect {
         // Cut the given rectangle into three vertical pieces
         let popup_layout = Layout::default()
@@ -227,21 +476,66 @@ impl App {
 
     fn render_left_footer(&self, buf: &mut Buffer, area: Rect) {
         let mode_spans = match self.state {
+            _ if self.editor.is_some() => vec![
+                Span::styled("Edit mode", Style::default().fg(COLOR_ACCENT1)),
+                Span::styled(" | ", Style::default().fg(Color::White)),
+                Span::styled(
+                    "(ESC) cancel / (TAB)/↑↓ switch field / (^V) paste / (ENTER) save bookmark",
+                    Style::default().fg(COLOR_ACCENT2),
+                ),
+            ],
+            _ if self.action_menu.is_some() => vec![
+                Span::styled("Actions", Style::default().fg(COLOR_ACCENT1)),
+                Span::styled(" | ", Style::default().fg(Color::White)),
+                Span::styled(
+                    "(ESC) cancel / ↑↓ select action / (ENTER) run",
+                    Style::default().fg(COLOR_ACCENT2),
+                ),
+            ],
+            _ if self.qr_code.is_some() => vec![
+                Span::styled("QR code", Style::default().fg(COLOR_ACCENT1)),
+                Span::styled(" | ", Style::default().fg(Color::White)),
+                Span::styled("(ESC)/(ENTER) close", Style::default().fg(COLOR_ACCENT2)),
+            ],
+            _ if self.details.is_some() => vec![
+                Span::styled("Details", Style::default().fg(COLOR_ACCENT1)),
+                Span::styled(" | ", Style::default().fg(Color::White)),
+                Span::styled("(ESC)/(ENTER) close", Style::default().fg(COLOR_ACCENT2)),
+            ],
+            _ if self.open_with.is_some() => vec![
+                Span::styled("Open with", Style::default().fg(COLOR_ACCENT1)),
+                Span::styled(" | ", Style::default().fg(Color::White)),
+                Span::styled(
+                    "(ESC) cancel / ↑↓ select browser / (ENTER) open / (W) new window / (P) private window / (A) app window",
+                    Style::default().fg(COLOR_ACCENT2),
+                ),
+            ],
             AppState::Bookmarks => {
                 vec![
                             Span::styled("Search mode", Style::default().fg(COLOR_ACCENT1)),
                             Span::styled(" | ", Style::default().fg(Color::White)),
                             Span::styled(
-                                "(ESC) exit / (PgUp)/(PgDwn) switch mode / ↑↓ select bookmark / (ENTER) open bookmark",
+                                format!("(ESC) exit / (PgUp)/(PgDwn) switch mode / ↑↓ select bookmark / (ENTER) open bookmark / ({}) actions / (^O) open with / (^N) add / (^E) edit / (^D) delete", self.action_key_label()),
                                 Style::default().fg(COLOR_ACCENT2),
                             ),
                         ]
             }
+            AppState::History => vec![
+                Span::styled("History mode", Style::default().fg(COLOR_ACCENT1)),
+                Span::styled(" | ", Style::default().fg(Color::White)),
+                Span::styled(
+                    "(ESC) exit / (PgUp)/(PgDwn) switch mode / ↑↓ select page / (ENTER) open page",
+                    Style::default().fg(COLOR_ACCENT2),
+                ),
+            ],
             AppState::Launcher => vec![
                 Span::styled("Launcher mode", Style::default().fg(COLOR_ACCENT1)),
                 Span::styled(" | ", Style::default().fg(Color::White)),
                 Span::styled(
-                    "(ESC) exit / (PgUp)/(PgDwn) switch mode / ↑↓ select item / (ENTER) launch",
+                    format!(
+                        "(ESC) exit / (PgUp)/(PgDwn) switch mode / ↑↓ select item / (ENTER) launch / ({}) actions",
+                        self.action_key_label()
+                    ),
                     Style::default().fg(COLOR_ACCENT2),
                 ),
             ],
@@ -254,6 +548,16 @@ impl App {
             .render(area, buf);
     }
 
+    /// Returns the key that opens the action menu, as shown in the footer.
+    fn action_key_label(&self) -> String {
+        self.config_manager
+            .config
+            .action_key
+            .as_deref()
+            .unwrap_or("Tab")
+            .to_uppercase()
+    }
+
     fn render_right_footer(&self, buf: &mut Buffer, area: Rect) {
         let status_spans = vec![
             Span::styled("Status: ", Style::default()),
@@ -273,3 +577,12 @@ impl App {
             .render(area, buf);
     }
 }
+
+/// Formats a Unix timestamp as `YYYY-MM-DD HH:MM` in UTC.
+fn format_timestamp(secs: i64) -> String {
+    let iso = to_iso8601(secs);
+    match iso.split_once('T') {
+        Some((date, time)) => format!("{} {}", date, &time[..5]),
+        None => iso,
+    }
+}
@@ -1,4 +1,8 @@
+mod action;
 mod input;
 mod layout;
+mod qr;
 
-pub use input::{Control, InputHandler};
+pub use action::{Action, APP_ACTIONS, BOOKMARK_ACTIONS};
+pub use input::{Control, InputHandler, KeyBinding};
+pub use qr::QrCodeView;
@@ -0,0 +1,103 @@
+use anyhow::anyhow;
+use qrcode::{EcLevel, QrCode};
+use ratatui::{
+    style::{Color, Style},
+    text::{Line, Span},
+};
+
+/// Light modules around the code, which scanners need to find its edges. The QR spec requires
+/// four, and fewer fail to scan next to the dark background of many terminals.
+const QUIET_ZONE: usize = 4;
+
+/// A QR code of a URL, drawn with half-block characters so that one line of text holds two
+/// rows of modules and every module comes out roughly square.
+pub struct QrCodeView {
+    pub url: String,
+    /// Whether each module is dark, row by row, including the quiet zone.
+    modules: Vec<Vec<bool>>,
+}
+
+impl QrCodeView {
+    /// Encodes a URL, with the lowest error correction to keep the code small.
+    ///
+    /// # Returns
+    /// - `Ok(QrCodeView)` with the encoded URL.
+    /// - `Err(anyhow::Error)` if the URL is too long for a QR code.
+    pub fn new(url: &str) -> anyhow::Result<Self> {
+        let code = QrCode::with_error_correction_level(url, EcLevel::L)
+            .map_err(|e| anyhow!("Cannot encode URL as QR code: {}", e))?;
+        let width = code.width();
+        let size = width + 2 * QUIET_ZONE;
+        let mut modules = vec![vec![false; size]; size];
+        for (i, color) in code.to_colors().into_iter().enumerate() {
+            modules[i / width + QUIET_ZONE][i % width + QUIET_ZONE] = color == qrcode::Color::Dark;
+        }
+        Ok(QrCodeView {
+            url: url.to_string(),
+            modules,
+        })
+    }
+
+    /// Returns the columns and lines the code takes up when every module is `scale` columns
+    /// wide.
+    pub fn size(&self, scale: usize) -> (usize, usize) {
+        let modules = self.modules.len() * scale;
+        (modules, modules.div_ceil(2))
+    }
+
+    /// Returns the largest scale at which the code fits into the given columns and lines, or
+    /// zero if it does not fit at all.
+    pub fn scale_to_fit(&self, columns: usize, lines: usize) -> usize {
+        let modules = self.modules.len();
+        (columns / modules).min(lines * 2 / modules)
+    }
+
+    /// Draws the code, with dark modules in black on white so that it scans in dark terminals.
+    pub fn lines(&self, scale: usize) -> Vec<Line<'static>> {
+        let rows: Vec<&Vec<bool>> = self
+            .modules
+            .iter()
+            .flat_map(|row| std::iter::repeat_n(row, scale))
+            .collect();
+        let color = |dark: bool| if dark { Color::Black } else { Color::White };
+
+        rows.chunks(2)
+            .map(|pair| {
+                let top = pair[0];
+                let bottom = pair.get(1).copied();
+                let spans: Vec<Span> = (0..top.len() * scale)
+                    .map(|column| {
+                        let x = column / scale;
+                        // The last line of an odd number of rows has a light lower half.
+                        let bottom_dark = bottom.is_some_and(|row| row[x]);
+                        Span::styled("▀", Style::new().fg(color(top[x])).bg(color(bottom_dark)))
+                    })
+                    .collect();
+                Line::from(spans)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_qr_code_size() {
+        let view = QrCodeView::new("https://www.rust-lang.org").unwrap();
+        // Version 2 has 25 modules, plus the quiet zone on both sides.
+        assert_eq!(view.size(1), (33, 17));
+        assert_eq!(view.scale_to_fit(80, 24), 1);
+        assert_eq!(view.scale_to_fit(200, 60), 3);
+        assert_eq!(view.scale_to_fit(20, 60), 0);
+
+        let lines = view.lines(2);
+        assert_eq!(lines.len(), 33);
+        assert_eq!(lines[0].spans.len(), 66);
+        // The finder pattern in the top left corner starts with a dark module.
+        let corner = &lines[QUIET_ZONE].spans[2 * QUIET_ZONE];
+        assert_eq!(corner.style.fg, Some(Color::Black));
+        assert_eq!(corner.style.bg, Some(Color::Black));
+    }
+}